@@ -1,7 +1,14 @@
+use crate::calender::Poenitentia::{Abstinentia, Ieiunium, IeiuniumEtAbstinentia};
+use crate::config::REGIONES_FILE;
+use crate::config_parse::user_regio_file;
+use crate::data::Layers;
 use crate::rosary::Mysteries;
 use crate::rosary::Mysteries::{Glorious, Joyful, Luminous, Sorrowful};
-use crate::tui::{e, E};
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
+use crate::tui::{e, E, R};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
 
 pub fn weekday() -> &'static str {
     match chrono::offset::Local::now().weekday() {
@@ -16,12 +23,15 @@ pub fn weekday() -> &'static str {
 }
 
 pub fn get_daily_mystery_enum() -> Mysteries {
-    let current_time = chrono::offset::Local::now();
-    if let Some(special) = special(current_time) {
+    get_mystery_enum(chrono::offset::Local::now().date_naive())
+}
+
+/// Mysteries to pray on `date`
+pub fn get_mystery_enum(date: NaiveDate) -> Mysteries {
+    if let Some(special) = special(date) {
         return special;
     }
-    let weekday = chrono::offset::Local::now().weekday();
-    match weekday {
+    match date.weekday() {
         Weekday::Mon => Joyful,
         Weekday::Tue => Sorrowful,
         Weekday::Wed => Glorious,
@@ -32,41 +42,39 @@ pub fn get_daily_mystery_enum() -> Mysteries {
     }
 }
 
-fn special(time: DateTime<Local>) -> Option<Mysteries> {
-    let easter = bdays::easter::easter_naive_date(time.year());
+fn special(date: NaiveDate) -> Option<Mysteries> {
+    let easter = bdays::easter::easter_naive_date(date.year());
     if easter.is_err() {
         return None;
     }
     // On Easter Sunday, pray Glorious
     let easter = easter.unwrap();
-    if easter.eq(&time.naive_local().date()) {
+    if easter.eq(&date) {
         return Some(Glorious);
     }
     let ash_wednesday = easter.checked_sub_signed(chrono::Duration::days(46))?;
-    let days_since_ash_wednesday = (time.naive_local().date() - ash_wednesday).num_days();
-    let days_since_easter = (time.naive_local().date() - easter).num_days();
+    let days_since_ash_wednesday = (date - ash_wednesday).num_days();
+    let days_since_easter = (date - easter).num_days();
     // double checking
     // On Lent Sundays, pray Sorrowful
-    if 0 > days_since_ash_wednesday
-        && days_since_ash_wednesday <= 46
+    if (0..=46).contains(&days_since_ash_wednesday)
         && days_since_easter < 0
-        && time.weekday() == Weekday::Sun
+        && date.weekday() == Weekday::Sun
     {
         return Some(Sorrowful);
     }
     // On Advent and Christmas Sundays, pray Joyful
-    let christmas = NaiveDate::from_ymd_opt(time.year(), 12, 24)?;
-    let mut fourth_advent = christmas.clone();
+    let christmas = NaiveDate::from_ymd_opt(date.year(), 12, 24)?;
+    let mut fourth_advent = christmas;
     while fourth_advent.weekday() != Weekday::Sun {
         fourth_advent = fourth_advent.pred_opt()?;
     }
     let first_advent = fourth_advent.checked_sub_signed(chrono::Duration::weeks(3))?;
-    let days_since_first_advent = (time.naive_local().date() - first_advent).num_days();
-    let days_since_fourth_advent = (time.naive_local().date() - fourth_advent).num_days();
-    if 0 > days_since_first_advent
-        && days_since_first_advent <= 7 * 4
+    let days_since_first_advent = (date - first_advent).num_days();
+    let days_since_fourth_advent = (date - fourth_advent).num_days();
+    if (0..=7 * 4).contains(&days_since_first_advent)
         && days_since_fourth_advent <= 0
-        && time.weekday() == Weekday::Sun
+        && date.weekday() == Weekday::Sun
     {
         return Some(Joyful);
     }
@@ -114,12 +122,21 @@ pub struct AnnusLiturgicus {
     festum_nativitatis_domini: NaiveDate,
     festum_st_johannis_evangelistae: NaiveDate,
     third_christmas_day: NaiveDate,
+    sancti_iosephi: NaiveDate,
+    corpus_christi: NaiveDate,
+    ss_petri_et_pauli: NaiveDate,
+    assumptio_beatae_mariae_virginis: NaiveDate,
+    immaculata_conceptio: NaiveDate,
+    /// Episcopal conference whose holy days of obligation and transfers apply
+    regio: Regio,
+    /// Pascha according to the Julian computus, used for the Byzantine fasts
+    pascha_orientalis: NaiveDate,
 }
 
 type LiturgicalDate = (&'static str, NaiveDate);
 
 impl AnnusLiturgicus {
-    pub fn new(year: i32) -> Result<AnnusLiturgicus, E> {
+    pub fn new(year: i32, regio: &Regio) -> Result<AnnusLiturgicus, E> {
         let easter = pascha(year)?;
         let dies_cinerum = days_before(easter, 46)?;
         let quinquagesima = sunday_before(dies_cinerum)?;
@@ -127,11 +144,17 @@ impl AnnusLiturgicus {
         let festum_nativitatis_domini =
             NaiveDate::from_ymd_opt(year, 12, 25).ok_or("no nativity date")?;
         let fourth_advent = sunday_before(festum_nativitatis_domini)?;
+        let festum_circumcisionis_domini =
+            NaiveDate::from_ymd_opt(year, 1, 1).ok_or("no circumcision date")?;
 
         Ok(AnnusLiturgicus {
-            festum_circumcisionis_domini: NaiveDate::from_ymd_opt(year, 1, 1)
-                .ok_or("no circumcision date")?,
-            epiphan_domini: NaiveDate::from_ymd_opt(year, 1, 6).ok_or("no epiphany date")?,
+            festum_circumcisionis_domini,
+            // Sunday between 2 and 8 January where transferred
+            epiphan_domini: if regio.epiphania_dominica {
+                sunday_after(festum_circumcisionis_domini)?
+            } else {
+                NaiveDate::from_ymd_opt(year, 1, 6).ok_or("no epiphany date")?
+            },
             // Purificatio Mariae
             praesentatio_domini: NaiveDate::from_ymd_opt(year, 2, 2)
                 .ok_or("no presentatio date")?,
@@ -157,7 +180,11 @@ impl AnnusLiturgicus {
             dominica_jubilate: weeks_after(easter, 3)?,
             dominica_cantate: weeks_after(easter, 4)?,
             dominica_rogate: weeks_after(easter, 5)?,
-            ascensio_domini: days_after(easter, 39)?,
+            ascensio_domini: if regio.ascensio_dominica {
+                weeks_after(easter, 6)?
+            } else {
+                days_after(easter, 39)?
+            },
             dominica_exaudi: weeks_after(easter, 6)?,
             pentecostes,
             dominica_trinitatis: sunday_after(pentecostes)?,
@@ -176,6 +203,20 @@ impl AnnusLiturgicus {
             festum_nativitatis_domini,
             festum_st_johannis_evangelistae: days_after(festum_nativitatis_domini, 1)?,
             third_christmas_day: days_after(festum_nativitatis_domini, 2)?,
+            sancti_iosephi: NaiveDate::from_ymd_opt(year, 3, 19).ok_or("No date for St. Joseph")?,
+            corpus_christi: if regio.corpus_christi_dominica {
+                weeks_after(pentecostes, 2)?
+            } else {
+                days_after(pentecostes, 11)?
+            },
+            ss_petri_et_pauli: NaiveDate::from_ymd_opt(year, 6, 29)
+                .ok_or("No date for Sts. Peter and Paul")?,
+            assumptio_beatae_mariae_virginis: NaiveDate::from_ymd_opt(year, 8, 15)
+                .ok_or("No date for the Assumption")?,
+            immaculata_conceptio: NaiveDate::from_ymd_opt(year, 12, 8)
+                .ok_or("No date for the Immaculate Conception")?,
+            regio: regio.clone(),
+            pascha_orientalis: pascha_orientalis(year)?,
         })
     }
 
@@ -195,6 +236,7 @@ impl AnnusLiturgicus {
             ("dominica_reminiscere", self.dominica_reminiscere),
             ("dominica_oculi", self.dominica_oculi),
             ("dominica_laetare", self.dominica_laetare),
+            ("sancti_iosephi", self.sancti_iosephi),
             (
                 "annuntiatio_beatae_mariae_virginis",
                 self.annuntiatio_beatae_mariae_virginis,
@@ -221,16 +263,23 @@ impl AnnusLiturgicus {
             ("dominica_exaudi", self.dominica_exaudi),
             ("pentecostes", self.pentecostes),
             ("dominica_trinitatis", self.dominica_trinitatis),
+            ("corpus_christi", self.corpus_christi),
             (
                 "nativitas_ioannis_baptistae",
                 self.nativitas_ioannis_baptistae,
             ),
+            ("ss_petri_et_pauli", self.ss_petri_et_pauli),
+            (
+                "assumptio_beatae_mariae_virginis",
+                self.assumptio_beatae_mariae_virginis,
+            ),
             ("festum_michaeli", self.festum_michaeli),
             ("omnium_sanctorum", self.omnium_sanctorum),
             ("festum_sancti_martini", self.festum_sancti_martini),
             ("first_advent", self.first_advent),
             ("second_advent", self.second_advent),
             ("third_advent", self.third_advent),
+            ("immaculata_conceptio", self.immaculata_conceptio),
             ("fourth_advent", self.fourth_advent),
             ("festum_nativitatis_domini", self.festum_nativitatis_domini),
             (
@@ -240,8 +289,382 @@ impl AnnusLiturgicus {
             ("third_christmas_day", self.third_christmas_day),
         ]
     }
+
+    /// Whether the feast named `name` is a holy day of obligation in the current region
+    pub fn is_praeceptum(&self, name: &str) -> bool {
+        self.regio.dies_praecepti.iter().any(|d| d == name)
+    }
+
+    /// Whether a holy day of obligation of the region falls on `date`, which may be a Sunday.
+    /// Sundays themselves are not counted
+    pub fn dies_praecepti(&self, date: NaiveDate) -> bool {
+        self.to_vec()
+            .iter()
+            .any(|(name, d)| *d == date && self.is_praeceptum(name))
+    }
+
+    /// Names of the feasts celebrated on `date`
+    pub fn festa(&self, date: NaiveDate) -> Vec<&'static str> {
+        self.to_vec()
+            .into_iter()
+            .filter(|(_, d)| *d == date)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Liturgical season `date` falls into
+    pub fn tempus(&self, date: NaiveDate) -> Tempus {
+        // Christmastide ends with the Baptism of the Lord, on the Monday after a late Epiphany
+        let baptismus_domini = if self.epiphan_domini.day() > 6 {
+            self.epiphan_domini.succ_opt()
+        } else {
+            sunday_after(self.epiphan_domini).ok()
+        };
+        if date >= self.first_advent && date < self.festum_nativitatis_domini {
+            Tempus::Adventus
+        } else if date >= self.festum_nativitatis_domini
+            || baptismus_domini.is_some_and(|b| date <= b)
+        {
+            Tempus::Nativitatis
+        } else if date >= self.dies_cinerum && date < self.dominica_resurrectionis_domini {
+            Tempus::Quadragesimae
+        } else if date >= self.dominica_resurrectionis_domini && date <= self.pentecostes {
+            Tempus::Paschale
+        } else {
+            Tempus::PerAnnum
+        }
+    }
+
+    /// Number of weeks since the first Sunday of Advent starting the liturgical year of `date`
+    pub fn hebdomada(&self, date: NaiveDate) -> Result<i64, E> {
+        let initium = if date >= self.first_advent {
+            self.first_advent
+        } else {
+            prima_dominica_adventus(self.first_advent.year() - 1)?
+        };
+        Ok((date - initium).num_days().div_euclid(7))
+    }
+
+    /// Return the kind of penance prescribed on `date` and the reason for it, if any
+    pub fn ieiunium(
+        &self,
+        date: NaiveDate,
+        disciplina: Disciplina,
+        omnes_feriae_sextae: bool,
+    ) -> Option<DiesPoenitentiae> {
+        match disciplina {
+            Disciplina::Hodierna => self.ieiunium_hodiernum(date, omnes_feriae_sextae),
+            Disciplina::Antiqua => self.ieiunium_antiquum(date),
+            Disciplina::Orientalis => self.ieiunium_orientale(date),
+        }
+    }
+
+    /// Fast and abstinence according to the Code of Canon Law of 1983
+    fn ieiunium_hodiernum(
+        &self,
+        date: NaiveDate,
+        omnes_feriae_sextae: bool,
+    ) -> Option<DiesPoenitentiae> {
+        if date == self.dies_cinerum {
+            return Some((IeiuniumEtAbstinentia, "dies_cinerum"));
+        }
+        if date == self.dies_passionis_domini {
+            return Some((IeiuniumEtAbstinentia, "dies_passionis_domini"));
+        }
+        if date.weekday() != Weekday::Fri {
+            return None;
+        }
+        // can. 1251, no abstinence on a solemnity, even in Lent
+        let sollemnitas = self
+            .to_vec()
+            .iter()
+            .any(|(name, d)| *d == date && SOLLEMNITATES.contains(name));
+        if sollemnitas {
+            return None;
+        }
+        if self.dies_cinerum < date && date < self.dominica_resurrectionis_domini {
+            return Some((Abstinentia, "quadragesima"));
+        }
+        let in_octava_paschae =
+            self.dominica_resurrectionis_domini < date && date < self.dominica_in_albis;
+        if omnes_feriae_sextae && !in_octava_paschae {
+            return Some((Abstinentia, "feria_sexta"));
+        }
+        None
+    }
+
+    /// Fast and abstinence according to the rubrics of 1962
+    fn ieiunium_antiquum(&self, date: NaiveDate) -> Option<DiesPoenitentiae> {
+        if date == self.dies_cinerum {
+            return Some((IeiuniumEtAbstinentia, "dies_cinerum"));
+        }
+        if date == self.dies_passionis_domini {
+            return Some((IeiuniumEtAbstinentia, "dies_passionis_domini"));
+        }
+        if self.quattuor_tempora().contains(&date) {
+            return Some((IeiuniumEtAbstinentia, "quattuor_tempora"));
+        }
+        if self.vigiliae().contains(&date) {
+            return Some((IeiuniumEtAbstinentia, "vigilia"));
+        }
+        let friday = date.weekday() == Weekday::Fri;
+        if self.dies_cinerum < date
+            && date < self.dominica_resurrectionis_domini
+            && date.weekday() != Weekday::Sun
+        {
+            return Some(if friday {
+                (IeiuniumEtAbstinentia, "quadragesima")
+            } else {
+                (Ieiunium, "quadragesima")
+            });
+        }
+        if friday && date != self.festum_nativitatis_domini {
+            return Some((Abstinentia, "feria_sexta"));
+        }
+        None
+    }
+
+    /// Ember days: Wednesday, Friday and Saturday after the first Sunday of Lent,
+    /// Pentecost, the third Sunday of September and the third Sunday of Advent
+    fn quattuor_tempora(&self) -> Vec<NaiveDate> {
+        let year = self.festum_nativitatis_domini.year();
+        let mut dominicae = vec![self.quadragesima, self.pentecostes, self.third_advent];
+        if let Some(september) = NaiveDate::from_weekday_of_month_opt(year, 9, Weekday::Sun, 3) {
+            dominicae.push(september);
+        }
+        dominicae
+            .iter()
+            .flat_map(|d| [3, 5, 6].map(|n| *d + Duration::days(n)))
+            .collect()
+    }
+
+    /// Vigils with fast and abstinence; a vigil falling on a Sunday is omitted
+    fn vigiliae(&self) -> Vec<NaiveDate> {
+        let year = self.festum_nativitatis_domini.year();
+        let mut vigiliae = vec![self.pentecostes - Duration::days(1)];
+        for (month, day) in [(8, 14), (10, 31), (12, 24)] {
+            if let Some(d) = NaiveDate::from_ymd_opt(year, month, day) {
+                vigiliae.push(d);
+            }
+        }
+        vigiliae.retain(|d| d.weekday() != Weekday::Sun);
+        vigiliae
+    }
+
+    /// Fasts of the Byzantine rite: Wednesdays and Fridays and the four fasting seasons.
+    /// Movable dates follow the Julian Pascha, fixed dates the civil (Gregorian) calendar
+    fn ieiunium_orientale(&self, date: NaiveDate) -> Option<DiesPoenitentiae> {
+        let year = date.year();
+        let pascha = self.pascha_orientalis;
+        let pentecostes = pascha + Duration::days(49);
+        let md = |m, d| NaiveDate::from_ymd_opt(year, m, d);
+        let within = |from: NaiveDate, to: NaiveDate| from <= date && date <= to;
+
+        if [md(1, 5), md(8, 29), md(9, 14)].contains(&Some(date))
+            || date == pascha - Duration::days(2)
+        {
+            return Some((IeiuniumEtAbstinentia, "dies_ieiunii_stricti"));
+        }
+        if within(pascha - Duration::days(48), pascha - Duration::days(1)) {
+            return Some((Ieiunium, "magna_quadragesima"));
+        }
+        // Publican and Pharisee, Bright Week, Trinity Week and the Twelve Days are fast-free
+        if within(pascha - Duration::days(69), pascha - Duration::days(64))
+            || within(pascha, pascha + Duration::days(6))
+            || within(pentecostes, pentecostes + Duration::days(6))
+            || date.ordinal() <= 4
+            || date >= md(12, 25)?
+        {
+            return None;
+        }
+        if within(pascha - Duration::days(55), pascha - Duration::days(49)) {
+            return Some((Abstinentia, "hebdomas_casei"));
+        }
+        if within(pentecostes + Duration::days(8), md(6, 28)?) {
+            return Some((Ieiunium, "ieiunium_apostolorum"));
+        }
+        if within(md(8, 1)?, md(8, 14)?) {
+            return Some((Ieiunium, "ieiunium_dormitionis"));
+        }
+        if within(md(11, 15)?, md(12, 24)?) {
+            return Some((Ieiunium, "ieiunium_nativitatis"));
+        }
+        match date.weekday() {
+            Weekday::Wed => Some((Ieiunium, "feria_quarta")),
+            Weekday::Fri => Some((Ieiunium, "feria_sexta")),
+            _ => None,
+        }
+    }
+}
+
+/// Days on which the Friday abstinence is lifted
+const SOLLEMNITATES: [&str; 11] = [
+    "festum_circumcisionis_domini",
+    "epiphan_domini",
+    "sancti_iosephi",
+    "annuntiatio_beatae_mariae_virginis",
+    "corpus_christi",
+    "nativitas_ioannis_baptistae",
+    "ss_petri_et_pauli",
+    "assumptio_beatae_mariae_virginis",
+    "omnium_sanctorum",
+    "immaculata_conceptio",
+    "festum_nativitatis_domini",
+];
+
+/// Namespace of the names of regions among the interface keys
+pub const REGIO_NAMESPACE: &str = "regio/";
+
+/// Holy days of obligation and transfers to Sunday of an episcopal conference
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Regio {
+    pub name: String,
+    /// Epiphany is celebrated on the Sunday between 2 and 8 January
+    #[serde(default)]
+    pub epiphania_dominica: bool,
+    /// Ascension is celebrated on the seventh Sunday of Easter
+    #[serde(default)]
+    pub ascensio_dominica: bool,
+    /// Corpus Christi is celebrated on the Sunday after Trinity Sunday
+    #[serde(default)]
+    pub corpus_christi_dominica: bool,
+    /// Feasts besides Sundays on which Mass attendance is obligatory
+    #[serde(default)]
+    pub dies_praecepti: Vec<String>,
 }
 
+/// Read the regions of all layers, those of layers of higher precedence replacing those
+/// of the same name
+pub fn load_regiones(layers: &Layers) -> Result<Vec<Regio>, E> {
+    let mut regiones: Vec<Regio> = vec![];
+    for layer in layers.iter().rev().filter(|l| l.exists(REGIONES_FILE)) {
+        let path = layer.describe(REGIONES_FILE);
+        let s = layer
+            .read(REGIONES_FILE)
+            .map_err(|err| format!("Unable to read {}: {}", path, err))?;
+        let layer_regiones: Vec<Regio> =
+            serde_yaml::from_str(&s).map_err(|err| format!("{}: {}", path, err))?;
+        for regio in layer_regiones {
+            match regiones.iter().position(|r| r.name == regio.name) {
+                Some(i) => regiones[i] = regio,
+                None => regiones.push(regio),
+            }
+        }
+    }
+    if regiones.is_empty() {
+        return Err(format!("No regions in any {}", REGIONES_FILE).into());
+    }
+    Ok(regiones)
+}
+
+/// Index of the region chosen last in `regiones`, 0 if none was saved or it is gone
+pub fn saved_regio(regiones: &[Regio]) -> usize {
+    user_regio_file()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|name| regiones.iter().position(|r| r.name == name.trim()))
+        .unwrap_or(0)
+}
+
+/// Keep `regio` as the region windows start with
+pub fn save_regio(regio: &Regio) -> R {
+    let path = user_regio_file().ok_or(e("No configuration directory to save the region in"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, regio.name.clone() + "\n")?;
+    Ok(())
+}
+
+/// Fasting discipline according to which days of penance are calculated
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Disciplina {
+    /// Code of Canon Law of 1983
+    Hodierna,
+    /// Rubrics of 1962, including Ember days and vigils
+    Antiqua,
+    /// Byzantine rite
+    Orientalis,
+}
+
+impl Disciplina {
+    pub fn cycle(&self) -> Disciplina {
+        match self {
+            Disciplina::Hodierna => Disciplina::Antiqua,
+            Disciplina::Antiqua => Disciplina::Orientalis,
+            Disciplina::Orientalis => Disciplina::Hodierna,
+        }
+    }
+}
+
+impl fmt::Display for Disciplina {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Disciplina::Hodierna => write!(f, "disciplina_hodierna"),
+            Disciplina::Antiqua => write!(f, "disciplina_antiqua"),
+            Disciplina::Orientalis => write!(f, "disciplina_orientalis"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Poenitentia {
+    Ieiunium,
+    Abstinentia,
+    IeiuniumEtAbstinentia,
+}
+
+impl fmt::Display for Poenitentia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ieiunium => write!(f, "ieiunium"),
+            Abstinentia => write!(f, "abstinentia"),
+            IeiuniumEtAbstinentia => write!(f, "ieiunium_et_abstinentia"),
+        }
+    }
+}
+
+/// Liturgical season
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Tempus {
+    Adventus,
+    Nativitatis,
+    Quadragesimae,
+    Paschale,
+    PerAnnum,
+}
+
+impl std::str::FromStr for Tempus {
+    type Err = String;
+
+    /// Parse the Latin or English name of a season
+    fn from_str(s: &str) -> Result<Tempus, String> {
+        match s.to_lowercase().as_str() {
+            "adventus" | "advent" => Ok(Tempus::Adventus),
+            "nativitatis" | "christmas" => Ok(Tempus::Nativitatis),
+            "quadragesimae" | "lent" => Ok(Tempus::Quadragesimae),
+            "paschale" | "easter" => Ok(Tempus::Paschale),
+            "per_annum" | "ordinary" => Ok(Tempus::PerAnnum),
+            _ => Err(format!("unknown season \"{}\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for Tempus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tempus::Adventus => write!(f, "tempus_adventus"),
+            Tempus::Nativitatis => write!(f, "tempus_nativitatis"),
+            Tempus::Quadragesimae => write!(f, "tempus_quadragesimae"),
+            Tempus::Paschale => write!(f, "tempus_paschale"),
+            Tempus::PerAnnum => write!(f, "tempus_per_annum"),
+        }
+    }
+}
+
+/// Kind of penance and the name of the day or season prescribing it
+pub type DiesPoenitentiae = (Poenitentia, &'static str);
+
 fn weekday_before(date: NaiveDate, weekday: Weekday) -> Result<NaiveDate, E> {
     let mut new_date = date
         .clone()
@@ -277,29 +700,48 @@ fn sunday_after(date: NaiveDate) -> Result<NaiveDate, E> {
 }
 
 fn weeks_before(date: NaiveDate, weeks: i64) -> Result<NaiveDate, E> {
-    let d = date.clone();
+    let d = date;
     d.checked_sub_signed(Duration::weeks(weeks))
         .ok_or_else(|| e("Can't calculate date"))
 }
 
 fn weeks_after(date: NaiveDate, weeks: i64) -> Result<NaiveDate, E> {
-    let d = date.clone();
+    let d = date;
     d.checked_add_signed(Duration::weeks(weeks))
         .ok_or_else(|| e("Can't calculate date"))
 }
 
 fn days_before(date: NaiveDate, days: i64) -> Result<NaiveDate, E> {
-    let d = date.clone();
+    let d = date;
     d.checked_sub_signed(Duration::days(days))
         .ok_or_else(|| e("Can't calculate date"))
 }
 
 fn days_after(date: NaiveDate, days: i64) -> Result<NaiveDate, E> {
-    let d = date.clone();
+    let d = date;
     d.checked_add_signed(Duration::days(days))
         .ok_or_else(|| e("Can't calculate date"))
 }
 
+fn prima_dominica_adventus(year: i32) -> Result<NaiveDate, E> {
+    let nativitas = NaiveDate::from_ymd_opt(year, 12, 25).ok_or("no nativity date")?;
+    weeks_before(sunday_before(nativitas)?, 3)
+}
+
 fn pascha(year: i32) -> Result<NaiveDate, E> {
     bdays::easter::easter_naive_date(year).map_err(|_| e("Can't calculate Easter"))
 }
+
+/// Pascha according to the Julian computus, converted to the Gregorian calendar
+fn pascha_orientalis(year: i32) -> Result<NaiveDate, E> {
+    let a = year % 4;
+    let b = year % 7;
+    let c = year % 19;
+    let d = (19 * c + 15) % 30;
+    let e_ = (2 * a + 4 * b - d + 34) % 7;
+    let month = (d + e_ + 114) / 31;
+    let day = (d + e_ + 114) % 31 + 1;
+    let julian = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+        .ok_or_else(|| e("Can't calculate Pascha"))?;
+    days_after(julian, (year / 100 - year / 400 - 2) as i64)
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::calender::{load_regiones, REGIO_NAMESPACE};
+use crate::config::{LANGUAGE_FILE, TITLE_FILE};
+use crate::config_parse::{get_all_pages, ConfigError, OrderEntry, PrayerSetConfig};
+use crate::corpus::Corpus;
+use crate::data::{self, Layers};
+use crate::language::{Language, LanguageInfo, ORDINAL_NAMESPACE, UI_KEYS, UI_NAMESPACE};
+
+/// Title keys used by the interface rather than by prayer files or pages
+const UI_TITLES: [&str; 4] = ["rosarium", "pro_fide", "pro_spe", "pro_caritate"];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// Exit status of `rosarium check`: 1 on errors, or with `strict` on any finding
+pub fn exit_code(findings: &[Finding], strict: bool) -> i32 {
+    let failed = findings
+        .iter()
+        .any(|f| strict || f.severity == Severity::Error);
+    if failed {
+        1
+    } else {
+        0
+    }
+}
+
+fn warning(message: String) -> Finding {
+    Finding {
+        severity: Severity::Warning,
+        message,
+    }
+}
+
+fn error(message: String) -> Finding {
+    Finding {
+        severity: Severity::Error,
+        message,
+    }
+}
+
+/// Load every page of .config.yaml, resolve its prayers and groups in every language
+/// and report what is missing or unused
+pub fn check() -> Vec<Finding> {
+    check_in(data::layers(), get_all_pages())
+}
+
+/// Check `pages` against the languages and titles in `layers`
+pub fn check_in(
+    layers: &Layers,
+    pages: Result<Vec<Option<PrayerSetConfig>>, ConfigError>,
+) -> Vec<Finding> {
+    let pages = match pages {
+        Ok(pages) => pages,
+        Err(err) => return vec![error(err.to_string())],
+    };
+    let languages: Vec<Language> = Corpus::default()
+        .languages(layers)
+        .iter()
+        .map(|(dir, _)| Language::new(dir))
+        .collect();
+    let mut findings = check_languages(layers, &languages);
+    let mut needed_titles = BTreeSet::new();
+    for (i, page) in pages.iter().enumerate() {
+        let config = match page {
+            Some(config) => config,
+            None => {
+                findings.push(warning(format!("page {} is empty", i + 1)));
+                continue;
+            }
+        };
+        if config.order.is_empty() {
+            findings.push(warning(format!("page \"{}\" has no prayers", config.title)));
+        }
+        needed_titles.insert(config.title.clone());
+        for (prayer, group_reference) in referenced_prayers(config) {
+            let missing: Vec<&Language> = languages
+                .iter()
+                .filter(|lan| !prayer_exists(layers, lan, &prayer))
+                .collect();
+            if missing.len() == languages.len() {
+                findings.push(error(if group_reference {
+                    format!(
+                        "page \"{}\": group \"{}\" is not defined and no prayer of that name exists",
+                        config.title, prayer
+                    )
+                } else {
+                    format!(
+                        "page \"{}\": prayer \"{}\" not found in any language",
+                        config.title, prayer
+                    )
+                }));
+                continue;
+            }
+            for lan in missing {
+                findings.push(warning(format!(
+                    "page \"{}\": prayer \"{}\" missing in {}",
+                    config.title, prayer, lan
+                )));
+            }
+            needed_titles.insert(prayer);
+        }
+    }
+    needed_titles.extend(UI_KEYS.iter().map(|key| String::from(UI_NAMESPACE) + key));
+    match load_regiones(layers) {
+        Ok(regiones) => needed_titles.extend(
+            regiones
+                .iter()
+                .map(|regio| String::from(UI_NAMESPACE) + REGIO_NAMESPACE + &regio.name),
+        ),
+        Err(err) => findings.push(error(err.to_string())),
+    }
+    for lan in &languages {
+        findings.append(&mut check_titles(layers, lan, &needed_titles, &languages));
+    }
+    findings
+}
+
+/// Report invalid .lingua files and fallbacks to languages that do not exist
+fn check_languages(layers: &Layers, languages: &[Language]) -> Vec<Finding> {
+    let mut findings = vec![];
+    if languages.is_empty() {
+        findings.push(error(format!("no language found, add a {}", LANGUAGE_FILE)));
+    }
+    for lan in languages {
+        let filename = lan.to_string() + "/" + LANGUAGE_FILE;
+        let info = layers
+            .read(&filename)
+            .map_err(|err| err.to_string())
+            .and_then(|s| LanguageInfo::parse(&s).map_err(|err| err.to_string()));
+        let info = match info {
+            Ok(info) => info,
+            Err(err) => {
+                findings.push(error(format!("{}: {}", filename, err)));
+                continue;
+            }
+        };
+        for fallback in info.fallback {
+            if !languages.contains(&Language::new(&fallback)) {
+                findings.push(warning(format!(
+                    "{}: fallback \"{}\" is not a language",
+                    filename, fallback
+                )));
+            }
+        }
+    }
+    findings
+}
+
+/// Return every prayer a page can pray, and whether it was referenced like a group
+pub(crate) fn referenced_prayers(config: &PrayerSetConfig) -> BTreeSet<(String, bool)> {
+    let mut prayers = BTreeSet::new();
+    let mut groups = BTreeSet::new();
+    for entry in &config.order {
+        collect_prayers(config, entry, &mut groups, &mut prayers);
+    }
+    prayers
+}
+
+fn collect_prayers<'a>(
+    config: &'a PrayerSetConfig,
+    entry: &'a OrderEntry,
+    groups: &mut BTreeSet<&'a str>,
+    prayers: &mut BTreeSet<(String, bool)>,
+) {
+    let names: Vec<(&String, bool)> = match entry {
+        OrderEntry::Prayer(name) => vec![(name, false)],
+        OrderEntry::Group(group) => group.iter().map(|(name, _)| (name, true)).collect(),
+        OrderEntry::Sequence(entries, _) => {
+            for entry in entries {
+                collect_prayers(config, entry, groups, prayers);
+            }
+            return;
+        }
+    };
+    for (name, group_reference) in names {
+        match config.prayers.get(name) {
+            Some(members) => {
+                // Each group is only visited once
+                if groups.insert(name) {
+                    for member in members {
+                        collect_prayers(config, member, groups, prayers);
+                    }
+                }
+            }
+            None => {
+                prayers.insert((name.clone(), group_reference));
+            }
+        }
+    }
+}
+
+fn prayer_exists(layers: &Layers, lan: &Language, prayer: &str) -> bool {
+    layers.exists(&(lan.to_string() + "/" + prayer))
+}
+
+/// Report titles missing from or unused in the .tituli files of a language
+fn check_titles(
+    layers: &Layers,
+    lan: &Language,
+    needed: &BTreeSet<String>,
+    languages: &[Language],
+) -> Vec<Finding> {
+    let filename = lan.to_string() + "/" + TITLE_FILE;
+    let tituli = layers.read_all(&filename);
+    if tituli.is_empty() {
+        return vec![warning(format!("{} not found", filename))];
+    }
+    let keys: BTreeSet<&str> = tituli
+        .iter()
+        .flat_map(|tituli| tituli.lines())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, _)| key.trim())
+        .collect();
+
+    let mut findings = vec![];
+    for title in needed {
+        if !keys.contains(title.as_str()) {
+            findings.push(warning(format!(
+                "{}/{}: no title for \"{}\"",
+                lan, TITLE_FILE, title
+            )));
+        }
+    }
+    for key in keys {
+        let used = needed.contains(key)
+            || UI_TITLES.contains(&key)
+            || key.starts_with(&(String::from(UI_NAMESPACE) + ORDINAL_NAMESPACE))
+            || languages.iter().any(|l| prayer_exists(layers, l, key));
+        if !used {
+            findings.push(warning(format!(
+                "{}/{}: title \"{}\" is unused",
+                lan, TITLE_FILE, key
+            )));
+        }
+    }
+    findings
+}
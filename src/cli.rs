@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use crate::tui::{e, E};
+
+pub const USAGE: &str =
+    "Usage: rosarium [check [--strict]|coverage] [--seed <number>] [--data-dir <dir>]
+
+check             Lint prayer sets and translations instead of starting
+--strict          Let check fail on warnings as well as on errors
+coverage          Show which languages lack which prayer texts, titles and audio
+--seed <number>   Seed for the random selection of prayers, by default the day
+--data-dir <dir>  Prayer data overriding ROSARIUM_DATA, XDG data dirs and the bundled data";
+
+/// Command line arguments
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Args {
+    /// Lint prayer sets and translations instead of starting the interface
+    pub check: bool,
+    /// Fail the check on warnings too
+    pub strict: bool,
+    /// Report the translation coverage instead of starting the interface
+    pub coverage: bool,
+    /// Seed for the random selection of prayers
+    pub seed: Option<u64>,
+    /// Data directory looked up before all others
+    pub data_dir: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parse arguments, without the program name
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, E> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            match flag {
+                "check" => parsed.check = true,
+                "coverage" => parsed.coverage = true,
+                "--strict" => parsed.strict = true,
+                "--seed" => {
+                    let seed = value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| e("--seed requires a number"))?;
+                    parsed.seed = Some(seed.parse().map_err(|_| e("--seed requires a number"))?);
+                }
+                "--data-dir" => {
+                    let dir = value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| e("--data-dir requires a directory"))?;
+                    parsed.data_dir = Some(PathBuf::from(dir));
+                }
+                _ => return Err(format!("Unknown argument \"{}\"", arg).into()),
+            }
+        }
+        if parsed.strict && !parsed.check {
+            return Err(e("--strict only applies to check"));
+        }
+        Ok(parsed)
+    }
+}
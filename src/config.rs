@@ -1,6 +1,25 @@
 pub const ROSARY_CROSS: &str = "🕇✝♱✟🕆✞";
 pub const ROSARY_BEAD: &str = "•";
-pub const PRAYER_DIR: &str = "./preces";
+/// Prayer data bundled with the sources, the last data directory looked up in debug
+/// builds. Release builds read the embedded data or the installed data directories
+#[cfg(debug_assertions)]
+pub const PRAYER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/preces");
+/// Relative to XDG_DATA_HOME and each of XDG_DATA_DIRS
+pub const DATA_DIR: &str = "rosarium";
+/// Prayer sets, at the top of a data directory
+pub const CONFIG_FILE: &str = ".config.yaml";
 pub const TITLE_FILE: &str = ".tituli";
+/// Metadata of a language, in its directory
+pub const LANGUAGE_FILE: &str = ".lingua";
+/// Directory of the language windows start with
+pub const DEFAULT_LANGUAGE: &str = "latina";
 pub const MYSTERY_DIR: &str = "mysteria";
+/// Recordings of the prayers, in the directory of a language
+pub const AUDIO_DIR: &str = "cantus";
 pub const INITIUM_FILE: &str = "initium";
+/// Regions of the calendar, at the top of a data directory
+pub const REGIONES_FILE: &str = ".regiones.yaml";
+/// Relative to XDG_CONFIG_HOME
+pub const USER_CONFIG_FILE: &str = "rosarium/config.yaml";
+/// Name of the region chosen last, relative to XDG_CONFIG_HOME
+pub const USER_REGIO_FILE: &str = "rosarium/regio";
@@ -1,153 +1,914 @@
-use std::fs::read_to_string;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
 
+use crate::calender::{AnnusLiturgicus, Tempus};
+use crate::config::{CONFIG_FILE, USER_CONFIG_FILE, USER_REGIO_FILE};
+use crate::data::{self, Dir, Layers, Vfs};
 use crate::tui::{e, E};
-use linked_hash_map::LinkedHashMap;
+use chrono::{Datelike, NaiveDate, Weekday};
 use rand::{rngs::StdRng, seq::SliceRandom, Rng};
-use yaml_rust::{Yaml, YamlLoader};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use thiserror::Error;
 
 use crate::prayer::{Prayer, _Prayer};
 
-const Y: &str = "Malformed YAML";
 type PrayerList = Vec<Box<dyn Prayer>>;
 
-/// Return a list of all prayer set titles and corresponding YAML
-pub fn get_all_prayset_titles() -> Result<Vec<(String, Yaml)>, E> {
-    let s = read_to_string("preces/.config.yaml")?;
-    let y: Vec<Yaml> = YamlLoader::load_from_str(&s)?;
-    let mut titles: Vec<(String, Yaml)> = vec![];
-    for i in 0..y.len() {
-        let title = y[i]["title"].as_str();
-        if title.is_some() {
-            titles.push((String::from(title.unwrap()), y[i].clone()));
+/// Key introducing a sequence with properties in "order" or a group
+const SEQUENCE: &str = "sequence";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Unable to read {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{}", parse_error_message(.title, *.page, *.order_index, .message, .location))]
+    Parse {
+        /// Title of the page, if it could be read
+        title: Option<String>,
+        /// Number of the page within the file, starting at 1
+        page: usize,
+        /// Index of the offending entry in "order"
+        order_index: Option<usize>,
+        message: String,
+        /// Line and column, starting at 1
+        location: Option<(usize, usize)>,
+    },
+    #[error("Prayer group in page \"{title}\" contains itself: {}", .path.join(" -> "))]
+    Cycle {
+        title: String,
+        /// Groups leading from a group back to itself
+        path: Vec<String>,
+    },
+    #[error("Invalid include \"{pattern}\": {message}")]
+    Include { pattern: String, message: String },
+    #[error("{path} includes itself")]
+    IncludeCycle { path: String },
+    #[error("In {path}: {source}")]
+    InFile {
+        path: String,
+        #[source]
+        source: Box<ConfigError>,
+    },
+}
+
+fn parse_error_message(
+    title: &Option<String>,
+    page: usize,
+    order_index: Option<usize>,
+    message: &str,
+    location: &Option<(usize, usize)>,
+) -> String {
+    let mut s = match title {
+        Some(title) => format!("Malformed YAML in page \"{}\"", title),
+        None => format!("Malformed YAML in page {}", page),
+    };
+    if let Some(i) = order_index {
+        s += &format!(", order entry {}", i);
+    }
+    if let Some((line, column)) = location {
+        s += &format!(" (line {}, column {})", line, column);
+    }
+    s + ": " + message
+}
+
+/// One page of .config.yaml, representing one tab of Rosarium
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrayerSetConfig {
+    pub title: String,
+    /// Groups of prayers that can be used in the order, members are written like entries in
+    /// the order and can themselves be groups or sequences
+    #[serde(default)]
+    pub prayers: HashMap<String, Vec<OrderEntry>>,
+    #[serde(default)]
+    pub order: Vec<OrderEntry>,
+}
+
+/// An entry in "order"
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum OrderEntry {
+    /// A prayer file or group name
+    Prayer(String),
+    /// Prayer files or groups with properties applied
+    Group(Vec<(String, Properties)>),
+    /// Entries prayed one after another, as a whole selected by the properties.
+    /// Written as a list, or as a map whose first key is `sequence` followed by properties
+    Sequence(Vec<OrderEntry>, Properties),
+}
+
+/// Something selectable from a group
+trait Member {
+    /// Relative likelihood of random selection
+    fn weight(&self) -> u32 {
+        1
+    }
+}
+
+impl Member for String {}
+
+impl Member for Vec<OrderEntry> {}
+
+impl Member for OrderEntry {
+    /// Weight given in the properties, summed for several prayers written as one map
+    fn weight(&self) -> u32 {
+        match self {
+            OrderEntry::Prayer(_) => 1,
+            OrderEntry::Group(group) => group.iter().map(|(_, p)| p.weight).sum(),
+            OrderEntry::Sequence(_, properties) => properties.weight,
         }
     }
-    return Ok(titles);
 }
 
-/// Return yaml page corresponding to title
-pub fn get_yaml_for_title<'a>(title: &str, y: &'a Vec<Yaml>) -> Option<&'a Yaml> {
-    for page in y {
-        if page["title"].as_str() == Some(title) {
-            return Some(&page);
+impl<'de> Deserialize<'de> for OrderEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderEntryVisitor;
+
+        impl<'de> Visitor<'de> for OrderEntryVisitor {
+            type Value = OrderEntry;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(
+                    f,
+                    "a prayer name, a map of prayer names to properties or a sequence"
+                )
+            }
+
+            fn visit_str<Er: de::Error>(self, v: &str) -> Result<OrderEntry, Er> {
+                Ok(OrderEntry::Prayer(v.to_string()))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OrderEntry, A::Error> {
+                let mut entries = vec![];
+                while let Some(entry) = seq.next_element()? {
+                    entries.push(entry);
+                }
+                Ok(OrderEntry::Sequence(entries, Properties::default()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderEntry, A::Error> {
+                let mut group = vec![];
+                while let Some(name) = map.next_key::<String>()? {
+                    if name == SEQUENCE && group.is_empty() {
+                        let entries = map.next_value()?;
+                        let properties = Properties::deserialize(MapAccessDeserializer::new(map))?;
+                        return Ok(OrderEntry::Sequence(entries, properties.validated()?));
+                    }
+                    let properties: Option<Properties> = map.next_value()?;
+                    group.push((name, properties.unwrap_or_default().validated()?));
+                }
+                Ok(OrderEntry::Group(group))
+            }
         }
+
+        deserializer.deserialize_any(OrderEntryVisitor)
     }
-    None
 }
 
-/// Return a list of prayers as defined in "order"
-pub fn get_order(rng: &mut StdRng, y: &Yaml) -> Result<PrayerList, E> {
-    let mut order: PrayerList = vec![];
-    let o = &y["order"].as_vec();
-    if o.is_none() {
-        return Ok(order);
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Properties {
+    /// Number of prayers to select, all prayers of the group if unset
+    #[serde(default)]
+    pub count: Option<Count>,
+    /// Whether to select prayers at random from group, same as `mode: random`
+    #[serde(default)]
+    pub random: bool,
+    /// How to select prayers from group
+    #[serde(default)]
+    pub mode: Mode,
+    /// Period after which `mode: cycle` advances to the next prayer
+    #[serde(default)]
+    pub period: Period,
+    /// Whether a prayer is selected at most once
+    #[serde(default)]
+    pub unique: bool,
+    /// Relative likelihood of random selection as a member of a group
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// Chance (in percent) to select any prayers from group at all
+    #[serde(default = "full_chance", deserialize_with = "deserialize_chance")]
+    pub chance: u8,
+    /// Only include prayers on days matching the condition
+    #[serde(default)]
+    pub when: Option<Condition>,
+    /// Never include prayers on days matching the condition
+    #[serde(default)]
+    pub unless: Option<Condition>,
+}
+
+impl Properties {
+    /// Reject properties contradicting each other
+    fn validated<Er: de::Error>(self) -> Result<Properties, Er> {
+        if self.random && self.mode == Mode::Cycle {
+            return Err(Er::custom(
+                "random: true contradicts mode: cycle, use one of them",
+            ));
+        }
+        Ok(self)
     }
-    let o = o.unwrap();
-    for p in 0..o.len() {
-        let prayer = o[p].as_str();
-        if prayer.is_some() {
-            order.push(Box::new(_Prayer::new(prayer.unwrap().to_string())))
-        } else {
-            let prayer = o[p].as_hash().ok_or(e(Y))?;
-            //println!("Group: {:?}", prayer);
-            order.append(&mut process_group(rng, y, prayer)?);
+
+    /// Whether the when and unless conditions allow praying on `date`
+    pub fn applies(&self, al: &AnnusLiturgicus, date: NaiveDate) -> bool {
+        self.when.as_ref().is_none_or(|c| c.holds(al, date))
+            && !self.unless.as_ref().is_some_and(|c| c.holds(al, date))
+    }
+}
+
+impl Default for Properties {
+    fn default() -> Self {
+        Properties {
+            count: None,
+            random: false,
+            mode: Mode::default(),
+            period: Period::default(),
+            unique: false,
+            weight: default_weight(),
+            chance: full_chance(),
+            when: None,
+            unless: None,
         }
     }
-    Ok(order)
 }
 
-/// Process a prayer group definition in "order"
-pub fn process_group(
-    rng: &mut StdRng,
-    y: &Yaml,
-    g: &LinkedHashMap<Yaml, Yaml>,
-) -> Result<PrayerList, E> {
-    let mut order: PrayerList = vec![];
-    for (var_name, properties) in g.iter() {
-        let var_name = var_name.as_str().ok_or(e(Y))?;
-        let group_prayers = expand_group_definition(y, var_name)?;
-        order.append(&mut pick_and_apply_properties(
-            rng,
-            &group_prayers,
-            &properties,
-        )?);
-    }
-    Ok(order)
-}
-
-pub fn expand_group_definition(y: &Yaml, g: &str) -> Result<PrayerList, E> {
-    let mut order: PrayerList = vec![];
-    let prayers = &y["prayers"][g];
-    if !prayers.is_badvalue() {
-        for prayer in prayers.as_vec().ok_or(e(Y))? {
-            order.push(Box::new(_Prayer::new(
-                prayer.as_str().ok_or(e(Y))?.to_string(),
-            )))
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Prayers in the order of the group
+    #[default]
+    Ordered,
+    /// Prayers at random, potentially repeating
+    Random,
+    /// Advance through the group by one selection each period
+    Cycle,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    #[default]
+    Day,
+    /// Weeks starting on Monday
+    Week,
+    /// Weeks starting on Sunday, counted from the first Sunday of Advent
+    LiturgicalWeek,
+}
+
+fn full_chance() -> u8 {
+    100
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn deserialize_chance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let chance = u8::deserialize(deserializer)?;
+    if chance > 100 {
+        return Err(de::Error::custom(format!(
+            "chance must be a percentage, found {}",
+            chance
+        )));
+    }
+    Ok(chance)
+}
+
+/// Condition on the day of prayer, every given field has to match.
+/// Each field is either a single value or a list of which one has to match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    /// Days of the week, like `fri` or `friday`
+    #[serde(default, deserialize_with = "deserialize_parsed")]
+    pub weekday: Option<Vec<Weekday>>,
+    /// Liturgical seasons, like `lent` or `quadragesimae`
+    #[serde(default, deserialize_with = "deserialize_parsed")]
+    pub season: Option<Vec<Tempus>>,
+    /// Feasts as named in the calendar, like `pentecostes`
+    #[serde(default, deserialize_with = "deserialize_one_or_many")]
+    pub feast: Option<Vec<String>>,
+    /// Months, starting at 1 for January
+    #[serde(default, deserialize_with = "deserialize_months")]
+    pub month: Option<Vec<u32>>,
+}
+
+impl Condition {
+    /// Whether `date` satisfies the condition, feasts are looked up in `al`
+    pub fn holds(&self, al: &AnnusLiturgicus, date: NaiveDate) -> bool {
+        self.weekday
+            .as_ref()
+            .is_none_or(|w| w.contains(&date.weekday()))
+            && self
+                .season
+                .as_ref()
+                .is_none_or(|s| s.contains(&al.tempus(date)))
+            && self.feast.as_ref().is_none_or(|f| {
+                al.festa(date)
+                    .iter()
+                    .any(|festum| f.iter().any(|n| n == festum))
+            })
+            && self
+                .month
+                .as_ref()
+                .is_none_or(|m| m.contains(&date.month()))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+fn deserialize_one_or_many<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<T>>, D::Error> {
+    Ok(Some(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(v) => vec![v],
+        OneOrMany::Many(v) => v,
+    }))
+}
+
+fn deserialize_parsed<'de, D: Deserializer<'de>, T>(
+    deserializer: D,
+) -> Result<Option<Vec<T>>, D::Error>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    let values: Option<Vec<String>> = deserialize_one_or_many(deserializer)?;
+    values
+        .unwrap_or_default()
+        .iter()
+        .map(|v| {
+            v.parse()
+                .map_err(|err| de::Error::custom(format!("{}: \"{}\"", err, v)))
+        })
+        .collect::<Result<Vec<T>, D::Error>>()
+        .map(Some)
+}
+
+fn deserialize_months<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<u32>>, D::Error> {
+    let months: Option<Vec<u32>> = deserialize_one_or_many(deserializer)?;
+    if let Some(month) = months.iter().flatten().find(|m| !(1..=12).contains(*m)) {
+        return Err(de::Error::custom(format!(
+            "month must be between 1 and 12, found {}",
+            month
+        )));
+    }
+    Ok(months)
+}
+
+/// Highest count accepted, far beyond any page prayed in one sitting
+pub const MAX_COUNT: usize = 1000;
+
+/// Select a random number between min and max of prayers, written as `3` or `"1-3"`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Count {
+    pub min: usize,
+    pub max: usize,
+}
+
+fn bounded<Er: de::Error>(count: Count) -> Result<Count, Er> {
+    if count.max > MAX_COUNT {
+        return Err(Er::custom(format!(
+            "count must be at most {}, found {}",
+            MAX_COUNT, count.max
+        )));
+    }
+    Ok(count)
+}
+
+impl<'de> Deserialize<'de> for Count {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CountVisitor;
+
+        impl Visitor<'_> for CountVisitor {
+            type Value = Count;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a number or a range like \"1-3\"")
+            }
+
+            fn visit_u64<Er: de::Error>(self, v: u64) -> Result<Count, Er> {
+                let v = usize::try_from(v).unwrap_or(usize::MAX);
+                bounded(Count { min: v, max: v })
+            }
+
+            fn visit_i64<Er: de::Error>(self, v: i64) -> Result<Count, Er> {
+                if v < 0 {
+                    return Err(Er::custom(format!(
+                        "count must not be negative, found {}",
+                        v
+                    )));
+                }
+                self.visit_u64(v as u64)
+            }
+
+            fn visit_str<Er: de::Error>(self, v: &str) -> Result<Count, Er> {
+                let invalid = || Er::invalid_value(de::Unexpected::Str(v), &self);
+                let (min, max) = v.split_once('-').unwrap_or((v, v));
+                let min: usize = min.trim().parse().map_err(|_| invalid())?;
+                let max: usize = max.trim().parse().map_err(|_| invalid())?;
+                if min > max {
+                    return Err(invalid());
+                }
+                bounded(Count { min, max })
+            }
         }
-    } else {
-        order.push(Box::new(_Prayer::new(g.to_string())))
+
+        deserializer.deserialize_any(CountVisitor)
     }
-    Ok(order)
 }
 
-struct Properties {
-    /// Select a random number between count_min and count_max of Prayers
-    count_min: usize,
-    count_max: usize,
-    /// Whether to select prayers at random from group
-    random: bool,
-    /// Chance (in percent) to select any prayers from group at all
-    chance: i64,
+/// Return all prayer sets defined in the configuration file
+pub fn get_all_prayset_titles() -> Result<Vec<PrayerSetConfig>, ConfigError> {
+    Ok(get_all_pages()?.into_iter().flatten().collect())
+}
+
+/// Return all pages of the configuration files and their includes, with the pages of
+/// data directories of higher precedence and then of the user configuration file
+/// replacing those of the same title. Empty pages are `None`
+pub fn get_all_pages() -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    let mut pages = layered_pages(data::layers())?;
+    if let Some(user_config) = user_config_file().filter(|f| f.is_file()) {
+        let user_pages = read_pages(&user_config)
+            .map_err(|err| in_file(&user_config.display().to_string(), err))?;
+        merge_pages(&mut pages, user_pages);
+    }
+    Ok(pages)
+}
+
+/// Return the pages of the configuration files of all layers, those of layers of higher
+/// precedence replacing pages with the same title
+pub fn layered_pages(layers: &Layers) -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    let mut pages: Option<Vec<Option<PrayerSetConfig>>> = None;
+    for layer in layers.iter().rev().filter(|l| l.exists(CONFIG_FILE)) {
+        let layer_pages = load_pages(layer, CONFIG_FILE, &mut vec![])
+            .map_err(|err| in_file(&layer.describe(CONFIG_FILE), err))?;
+        match pages.as_mut() {
+            Some(pages) => merge_pages(pages, layer_pages),
+            None => pages = Some(layer_pages),
+        }
+    }
+    pages.ok_or_else(|| ConfigError::Io {
+        path: CONFIG_FILE.to_string(),
+        source: io::Error::new(io::ErrorKind::NotFound, "not found in any data directory"),
+    })
+}
+
+/// XDG_CONFIG_HOME or ~/.config
+fn config_home() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
 }
 
-fn get_properties(group: &PrayerList, properties: &Yaml) -> Result<Properties, E> {
-    let mut count_min = group.len();
-    let mut count_max = count_min;
-    let _count = properties["count"].as_i64();
-    if _count.is_some() {
-        count_min = _count.unwrap() as usize;
-        count_max = _count.unwrap() as usize;
+/// Configuration file of the user, in XDG_CONFIG_HOME or ~/.config
+pub fn user_config_file() -> Option<PathBuf> {
+    Some(config_home()?.join(USER_CONFIG_FILE))
+}
+
+/// File keeping the region chosen last, in XDG_CONFIG_HOME or ~/.config
+pub fn user_regio_file() -> Option<PathBuf> {
+    Some(config_home()?.join(USER_REGIO_FILE))
+}
+
+/// Replace pages by those in `overrides` with the same title, append all others.
+/// Empty pages of `overrides` are skipped
+pub fn merge_pages(
+    pages: &mut Vec<Option<PrayerSetConfig>>,
+    overrides: Vec<Option<PrayerSetConfig>>,
+) {
+    for page in overrides.into_iter().flatten() {
+        let existing = pages
+            .iter()
+            .position(|p| p.as_ref().is_some_and(|p| p.title == page.title));
+        match existing {
+            Some(i) => pages[i] = Some(page),
+            None => pages.push(Some(page)),
+        }
+    }
+}
+
+/// Read the pages of a configuration file and its includes, empty pages are `None`
+pub fn read_pages(path: &Path) -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    let dir = Dir::new(path.parent().unwrap_or(Path::new("")));
+    let file = path.file_name().unwrap_or_default().to_string_lossy();
+    read_pages_in(&dir, &file)
+}
+
+/// Read the pages of the configuration file at `path` in `layer` and its includes
+pub fn read_pages_in(
+    layer: &dyn Vfs,
+    path: &str,
+) -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    load_pages(layer, path, &mut vec![])
+}
+
+/// Read the pages of a configuration file in `layer`, `files` are the files currently
+/// being read
+fn load_pages(
+    layer: &dyn Vfs,
+    path: &str,
+    files: &mut Vec<String>,
+) -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    let path = normalize(path);
+    if files.contains(&path) {
+        return Err(ConfigError::IncludeCycle {
+            path: layer.describe(&path),
+        });
+    }
+    let s = layer.read(&path).map_err(|source| ConfigError::Io {
+        path: layer.describe(&path),
+        source,
+    })?;
+    let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir).to_string();
+    files.push(path);
+    let pages = parse_documents(&s, layer, &dir, files);
+    files.pop();
+    pages
+}
+
+/// Read the pages of all files in `layer` matching `pattern`, relative to `dir`
+fn include_files(
+    layer: &dyn Vfs,
+    dir: &str,
+    pattern: &str,
+    files: &mut Vec<String>,
+) -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    let full_pattern = if dir.is_empty() {
+        pattern.to_string()
     } else {
-        let _count = properties["count"].as_str();
-        if _count.is_some() {
-            let _count: Vec<&str> = _count.unwrap().split("-").collect();
-            count_min = _count[0].parse()?;
-            count_max = _count[1].parse()?;
+        format!("{}/{}", dir, pattern)
+    };
+    let matches = layer
+        .glob(&full_pattern)
+        .map_err(|message| ConfigError::Include {
+            pattern: pattern.to_string(),
+            message,
+        })?;
+    let mut pages = vec![];
+    for path in matches {
+        let mut included =
+            load_pages(layer, &path, files).map_err(|err| in_file(&layer.describe(&path), err))?;
+        pages.append(&mut included);
+    }
+    Ok(pages)
+}
+
+/// Remove "." and resolvable ".." components, to recognize files included twice
+fn normalize(path: &str) -> String {
+    let mut components: Vec<&str> = vec![];
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." if components.last().is_some_and(|c| *c != "..") => {
+                components.pop();
+            }
+            _ => components.push(component),
         }
     }
+    components.join("/")
+}
 
-    //.unwrap_or(group.len() as i64) as usize;
-    let random = properties["random"].as_bool().unwrap_or(false);
-    let chance = properties["chance"].as_i64().unwrap_or(100);
+/// Name the file an error occurred in, unless the error already does
+fn in_file(path: &str, err: ConfigError) -> ConfigError {
+    match err {
+        ConfigError::Io { .. } | ConfigError::InFile { .. } | ConfigError::IncludeCycle { .. } => {
+            err
+        }
+        _ => ConfigError::InFile {
+            path: path.to_string(),
+            source: Box::new(err),
+        },
+    }
+}
 
-    Ok(Properties {
-        count_min,
-        count_max,
-        random,
-        chance,
-    })
+/// Parse all pages of a configuration file, skipping empty pages
+pub fn parse_prayer_sets(s: &str) -> Result<Vec<PrayerSetConfig>, ConfigError> {
+    Ok(parse_pages(s)?.into_iter().flatten().collect())
+}
+
+/// Parse all pages of a configuration file, empty pages are `None`.
+/// Includes are relative to the working directory
+pub fn parse_pages(s: &str) -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    parse_documents(s, &Dir::new(""), "", &mut vec![])
+}
+
+/// A page pulling in the pages of other files
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Include {
+    /// Glob patterns relative to the including file
+    #[serde(deserialize_with = "deserialize_one_or_many")]
+    include: Option<Vec<String>>,
+}
+
+fn parse_documents(
+    s: &str,
+    layer: &dyn Vfs,
+    dir: &str,
+    files: &mut Vec<String>,
+) -> Result<Vec<Option<PrayerSetConfig>>, ConfigError> {
+    // Titles are read leniently first so that errors can name the page they occur in
+    #[derive(Deserialize)]
+    struct Header {
+        title: Option<String>,
+        include: Option<de::IgnoredAny>,
+    }
+    let mut headers: Vec<Option<Header>> = vec![];
+    for page in serde_yaml::Deserializer::from_str(s) {
+        match Option::<Header>::deserialize(page) {
+            Ok(header) => headers.push(header),
+            // The iterator does not end after a syntax error
+            Err(_) => break,
+        }
+    }
+
+    let mut pages = vec![];
+    for (i, page) in serde_yaml::Deserializer::from_str(s).enumerate() {
+        let header = headers.get(i).and_then(|h| h.as_ref());
+        let located = |err: serde_path_to_error::Error<serde_yaml::Error>| {
+            let order_index = order_index(err.path());
+            let err = err.into_inner();
+            ConfigError::Parse {
+                title: header.and_then(|h| h.title.clone()),
+                page: i + 1,
+                order_index,
+                location: err.location().map(|l| (l.line(), l.column())),
+                message: strip_location(&err),
+            }
+        };
+        if header.is_some_and(|h| h.include.is_some()) {
+            let Include { include } = serde_path_to_error::deserialize(page).map_err(located)?;
+            for pattern in include.iter().flatten() {
+                pages.append(&mut include_files(layer, dir, pattern, files)?);
+            }
+            continue;
+        }
+        let config: Option<PrayerSetConfig> =
+            serde_path_to_error::deserialize(page).map_err(located)?;
+        if let Some(config) = &config {
+            if let Some(path) = find_cycle(config) {
+                return Err(ConfigError::Cycle {
+                    title: config.title.clone(),
+                    path,
+                });
+            }
+        }
+        pages.push(config);
+    }
+    Ok(pages)
+}
+
+/// Return the first chain of groups leading back to its start, like `a -> b -> a`
+fn find_cycle(config: &PrayerSetConfig) -> Option<Vec<String>> {
+    let mut names: Vec<&String> = config.prayers.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .find_map(|name| group_cycle(config, name, &mut vec![]))
+}
+
+fn group_cycle<'a>(
+    config: &'a PrayerSetConfig,
+    name: &'a str,
+    path: &mut Vec<&'a str>,
+) -> Option<Vec<String>> {
+    if let Some(start) = path.iter().position(|n| *n == name) {
+        let mut cycle: Vec<String> = path[start..].iter().map(|n| n.to_string()).collect();
+        cycle.push(name.to_string());
+        return Some(cycle);
+    }
+    let members = config.prayers.get(name)?;
+    path.push(name);
+    let cycle = members
+        .iter()
+        .find_map(|member| entry_cycle(config, member, path));
+    path.pop();
+    cycle
 }
 
-pub fn pick_and_apply_properties<'a>(
+fn entry_cycle<'a>(
+    config: &'a PrayerSetConfig,
+    entry: &'a OrderEntry,
+    path: &mut Vec<&'a str>,
+) -> Option<Vec<String>> {
+    match entry {
+        OrderEntry::Prayer(name) => group_cycle(config, name, path),
+        OrderEntry::Group(group) => group
+            .iter()
+            .find_map(|(name, _)| group_cycle(config, name, path)),
+        OrderEntry::Sequence(entries, _) => entries
+            .iter()
+            .find_map(|entry| entry_cycle(config, entry, path)),
+    }
+}
+
+fn order_index(path: &serde_path_to_error::Path) -> Option<usize> {
+    let mut segments = path.iter();
+    while let Some(segment) = segments.next() {
+        if let serde_path_to_error::Segment::Map { key } = segment {
+            if key == "order" {
+                if let Some(serde_path_to_error::Segment::Seq { index }) = segments.next() {
+                    return Some(*index);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Error message without the location serde_yaml appends, which is reported separately
+fn strip_location(err: &serde_yaml::Error) -> String {
+    let message = err.to_string();
+    match message.rfind(" at line ") {
+        Some(i) if err.location().is_some() => message[..i].to_string(),
+        _ => message,
+    }
+}
+
+/// Return a list of prayers as defined in "order" for `date`
+pub fn get_order(
     rng: &mut StdRng,
-    group: &PrayerList,
-    properties: &Yaml,
+    config: &PrayerSetConfig,
+    al: &AnnusLiturgicus,
+    date: NaiveDate,
 ) -> Result<PrayerList, E> {
-    let mut order: PrayerList = vec![];
-    let p = get_properties(group, properties)?;
+    Expansion {
+        rng,
+        config,
+        al,
+        date,
+        groups: vec![],
+    }
+    .entries(&config.order)
+}
+
+/// State while expanding the order of a page into prayers
+struct Expansion<'a> {
+    rng: &'a mut StdRng,
+    config: &'a PrayerSetConfig,
+    al: &'a AnnusLiturgicus,
+    date: NaiveDate,
+    /// Groups currently being expanded, to guard against cycles
+    groups: Vec<&'a str>,
+}
+
+impl<'a> Expansion<'a> {
+    /// Number of periods elapsed on the day of prayer
+    fn position(&self, properties: &Properties) -> Result<u64, E> {
+        Ok(match properties.period {
+            Period::Day => self.date.num_days_from_ce() as u64,
+            Period::Week => (self.date.num_days_from_ce() as u64 - 1) / 7,
+            Period::LiturgicalWeek => self.al.hebdomada(self.date)?.max(0) as u64,
+        })
+    }
 
-    if !rng.gen_bool(p.chance as f64 / 100.0) {
-        return Ok(order);
+    /// Select members of a group according to `properties` on the day of prayer
+    fn pick<T: Member>(
+        &mut self,
+        group: &'a [T],
+        properties: &Properties,
+    ) -> Result<Vec<&'a T>, E> {
+        let position = match properties.mode {
+            Mode::Cycle => self.position(properties)?,
+            _ => 0,
+        };
+        Ok(pick_and_apply_properties(
+            self.rng, group, properties, position,
+        ))
     }
 
-    let count: usize = rng.gen_range(p.count_min, p.count_max + 1);
+    fn entries(&mut self, entries: &'a [OrderEntry]) -> Result<PrayerList, E> {
+        let mut order: PrayerList = vec![];
+        for entry in entries {
+            order.append(&mut self.entry(entry)?);
+        }
+        Ok(order)
+    }
 
+    fn entry(&mut self, entry: &'a OrderEntry) -> Result<PrayerList, E> {
+        match entry {
+            OrderEntry::Prayer(name) => match self.config.prayers.get(name) {
+                Some(members) => self.group(name, members, &Properties::default()),
+                None => Ok(vec![Box::new(_Prayer::new(name.to_string()))]),
+            },
+            OrderEntry::Group(group) => self.process_group(group),
+            OrderEntry::Sequence(entries, properties) => {
+                let mut order: PrayerList = vec![];
+                if properties.applies(self.al, self.date) {
+                    let sequences = std::slice::from_ref(entries);
+                    for sequence in self.pick(sequences, properties)? {
+                        order.append(&mut self.entries(sequence)?);
+                    }
+                }
+                Ok(order)
+            }
+        }
+    }
+
+    /// Process prayers or groups with properties applied
+    fn process_group(&mut self, g: &'a [(String, Properties)]) -> Result<PrayerList, E> {
+        let mut order: PrayerList = vec![];
+        for (name, properties) in g {
+            if !properties.applies(self.al, self.date) {
+                continue;
+            }
+            match self.config.prayers.get(name) {
+                Some(members) => order.append(&mut self.group(name, members, properties)?),
+                None => {
+                    let prayers = std::slice::from_ref(name);
+                    for prayer in self.pick(prayers, properties)? {
+                        order.push(Box::new(_Prayer::new(prayer.to_string())));
+                    }
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Expand the members of the group `name` selected by `properties`
+    fn group(
+        &mut self,
+        name: &'a str,
+        members: &'a [OrderEntry],
+        properties: &Properties,
+    ) -> Result<PrayerList, E> {
+        if self.groups.contains(&name) {
+            return Err(e("Prayer group contains itself"));
+        }
+        self.groups.push(name);
+        let mut order: PrayerList = vec![];
+        for member in self.pick(members, properties)? {
+            order.append(&mut self.entry(member)?);
+        }
+        self.groups.pop();
+        Ok(order)
+    }
+}
+
+/// Select members of a group according to count, mode and chance.
+/// `position` is the number of periods elapsed, for `mode: cycle`
+fn pick_and_apply_properties<'a, T: Member>(
+    rng: &mut StdRng,
+    group: &'a [T],
+    p: &Properties,
+    position: u64,
+) -> Vec<&'a T> {
+    let mut selection = vec![];
+    if group.is_empty() || !rng.gen_bool(p.chance as f64 / 100.0) {
+        return selection;
+    }
+
+    // The whole group unless cycling, which takes the next member each period
+    let default = if p.mode == Mode::Cycle {
+        1
+    } else {
+        group.len()
+    };
+    let count = p.count.unwrap_or(Count {
+        min: default,
+        max: default,
+    });
+    let mut count: usize = rng.gen_range(count.min, count.max.saturating_add(1));
+    if p.unique {
+        count = count.min(group.len());
+    }
+
+    if p.random || p.mode == Mode::Random {
+        let mut remaining: Vec<usize> = (0..group.len()).collect();
+        for _ in 0..count {
+            // Fails once no member with a weight is left
+            let Ok(&i) = remaining.choose_weighted(rng, |&i| group[i].weight()) else {
+                break;
+            };
+            selection.push(&group[i]);
+            if p.unique {
+                remaining.retain(|&j| j != i);
+            }
+        }
+        return selection;
+    }
+
+    // Each period continues where the last one ended
+    let start = (position % group.len() as u64) as usize * count;
     for i in 0..count {
-        order.push(if p.random {
-            group.choose(rng).ok_or(e(Y))?.clone()
-        } else {
-            println!("{:?}", group.len());
-            group[i % group.len()].clone()
-        })
+        selection.push(&group[(start + i) % group.len()]);
     }
-    Ok(order)
+    selection
 }
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use crate::calender::{load_regiones, Regio};
+use crate::config::{LANGUAGE_FILE, TITLE_FILE};
+use crate::data::{self, Layers};
+use crate::language::{fallback_chain, Language, LanguageInfo};
+use crate::tui::E;
+
+static CORPUS: OnceLock<Mutex<Corpus>> = OnceLock::new();
+
+/// Prayer texts and titles read from the data layers, kept until invalidated.
+/// Missing files are remembered as well
+#[derive(Debug, Default)]
+pub struct Corpus {
+    texts: HashMap<String, Option<String>>,
+    /// Titles by key for each language, `None` if the language has no title file
+    titles: HashMap<String, Option<HashMap<String, String>>>,
+    files: HashMap<String, Option<PathBuf>>,
+    /// Directories and metadata of the languages, once discovered
+    languages: Option<Vec<(String, LanguageInfo)>>,
+    /// Regions of the calendar or the error reading them, once read
+    regiones: Option<Result<Vec<Regio>, String>>,
+}
+
+impl Corpus {
+    pub fn text(&mut self, layers: &Layers, path: &str) -> Option<String> {
+        self.texts
+            .entry(path.to_string())
+            .or_insert_with(|| layers.read(path).ok())
+            .clone()
+    }
+
+    /// `file` of `lan`, or else of the first of its fallback languages having it,
+    /// together with the language it was taken from
+    pub fn text_in(
+        &mut self,
+        layers: &Layers,
+        lan: &Language,
+        file: &str,
+    ) -> Option<(String, Language)> {
+        let fallbacks = fallback_chain(&lan.to_string(), self.languages(layers));
+        std::iter::once(lan.to_string())
+            .chain(fallbacks)
+            .find_map(|dir| {
+                self.text(layers, &(dir.clone() + "/" + file))
+                    .map(|text| (text, Language::new(&dir)))
+            })
+    }
+
+    /// Title of `key` in `lan`, the title files of earlier layers taking precedence
+    pub fn title(&mut self, layers: &Layers, lan: &str, key: &str) -> Option<Option<String>> {
+        let index = self.titles.entry(lan.to_string()).or_insert_with(|| {
+            let files = layers.read_all(&(lan.to_string() + "/" + TITLE_FILE));
+            if files.is_empty() {
+                return None;
+            }
+            let mut index = HashMap::new();
+            for tituli in files {
+                for (key, title) in tituli.lines().filter_map(|line| line.split_once(':')) {
+                    index
+                        .entry(key.trim().to_string())
+                        .or_insert_with(|| title.trim().to_string());
+                }
+            }
+            Some(index)
+        });
+        index.as_ref().map(|index| index.get(key).cloned())
+    }
+
+    /// Languages in the data layers, sorted by directory. A language whose .lingua file
+    /// is invalid is still listed, see `check` for the errors
+    pub fn languages(&mut self, layers: &Layers) -> &[(String, LanguageInfo)] {
+        self.languages.get_or_insert_with(|| {
+            let pattern = "*/".to_string() + LANGUAGE_FILE;
+            let mut dirs: Vec<String> = layers
+                .iter()
+                .flat_map(|layer| layer.glob(&pattern).unwrap_or_default())
+                .filter_map(|file| file.split_once('/').map(|(dir, _)| dir.to_string()))
+                .collect();
+            dirs.sort();
+            dirs.dedup();
+            dirs.into_iter()
+                .map(|dir| {
+                    let info = layers
+                        .read(&(dir.clone() + "/" + LANGUAGE_FILE))
+                        .ok()
+                        .and_then(|s| LanguageInfo::parse(&s).ok())
+                        .unwrap_or_else(|| LanguageInfo::unknown(&dir));
+                    (dir, info)
+                })
+                .collect()
+        })
+    }
+
+    /// Regions of the calendar of all layers
+    pub fn regiones(&mut self, layers: &Layers) -> Result<Vec<Regio>, E> {
+        self.regiones
+            .get_or_insert_with(|| load_regiones(layers).map_err(|err| err.to_string()))
+            .clone()
+            .map_err(|err| err.into())
+    }
+
+    /// The file at `path` on disk, e.g. for audio
+    pub fn file(&mut self, layers: &Layers, path: &str) -> Option<PathBuf> {
+        self.files
+            .entry(path.to_string())
+            .or_insert_with(|| layers.find(path))
+            .clone()
+    }
+}
+
+fn corpus() -> &'static Mutex<Corpus> {
+    CORPUS.get_or_init(Mutex::default)
+}
+
+/// Read the text at `path` from the corpus
+pub fn text(path: &str) -> io::Result<String> {
+    let text = corpus()
+        .lock()
+        .expect("corpus lock poisoned")
+        .text(data::layers(), path);
+    text.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} not found in any data directory", path),
+        )
+    })
+}
+
+/// Read `file` of `lan`, or else of the first of its fallback languages having it,
+/// together with the language it was taken from
+pub fn text_in(lan: &Language, file: &str) -> Option<(String, Language)> {
+    corpus()
+        .lock()
+        .expect("corpus lock poisoned")
+        .text_in(data::layers(), lan, file)
+}
+
+/// Title of `key` in `lan`, `None` if the language has no title file
+pub fn title(lan: &str, key: &str) -> Option<Option<String>> {
+    corpus()
+        .lock()
+        .expect("corpus lock poisoned")
+        .title(data::layers(), lan, key)
+}
+
+/// Languages in the data layers, sorted by directory
+pub fn languages() -> Vec<(String, LanguageInfo)> {
+    corpus()
+        .lock()
+        .expect("corpus lock poisoned")
+        .languages(data::layers())
+        .to_vec()
+}
+
+/// Regions of the calendar in the data layers
+pub fn regiones() -> Result<Vec<Regio>, E> {
+    corpus()
+        .lock()
+        .expect("corpus lock poisoned")
+        .regiones(data::layers())
+}
+
+/// The file at `path` on disk, from the first layer containing it
+pub fn file(path: &str) -> Option<PathBuf> {
+    corpus()
+        .lock()
+        .expect("corpus lock poisoned")
+        .file(data::layers(), path)
+}
+
+/// Run `f` on the corpus and the data layers
+pub fn with<T>(f: impl FnOnce(&mut Corpus, &Layers) -> T) -> T {
+    f(
+        &mut corpus().lock().expect("corpus lock poisoned"),
+        data::layers(),
+    )
+}
+
+/// Forget everything read, e.g. after files changed on disk
+pub fn invalidate() {
+    *corpus().lock().expect("corpus lock poisoned") = Corpus::default();
+}
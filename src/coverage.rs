@@ -0,0 +1,212 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::check::referenced_prayers;
+use crate::config::MYSTERY_DIR;
+use crate::config_parse::{get_all_pages, PrayerSetConfig};
+use crate::corpus::Corpus;
+use crate::data::{self, Layers};
+use crate::language::Language;
+use crate::prayer::{audio_path, Prayer};
+use crate::rosary::RosaryPrayer;
+use crate::tui::E;
+
+/// What a language has of a prayer file
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Presence {
+    pub text: bool,
+    /// `None` if the file needs no title
+    pub title: Option<bool>,
+    pub audio: bool,
+}
+
+impl Presence {
+    /// Whether a translator has something to add, audio is optional
+    pub fn is_gap(&self) -> bool {
+        !self.text || self.title == Some(false)
+    }
+
+    /// "Tta" with a dash for each missing part
+    pub fn abbreviation(&self) -> String {
+        [
+            if self.text { 'T' } else { '-' },
+            match self.title {
+                Some(true) => 't',
+                Some(false) => '-',
+                None => ' ',
+            },
+            if self.audio { 'a' } else { '-' },
+        ]
+        .iter()
+        .collect()
+    }
+}
+
+/// A prayer file and what each language has of it
+#[derive(Debug)]
+pub struct FileCoverage {
+    /// Path relative to the language directories
+    pub file: String,
+    /// By language, in the order of `Coverage::languages`
+    pub presence: Vec<Presence>,
+}
+
+/// Matrix of the prayer files of the rosary, the pages and the language directories
+/// against the languages
+#[derive(Debug)]
+pub struct Coverage {
+    pub languages: Vec<Language>,
+    pub files: Vec<FileCoverage>,
+}
+
+impl Coverage {
+    /// Gather the files of the rosary, of all pages, at the top of the language
+    /// directories and in the mysteria directories
+    pub fn new() -> Result<Coverage, E> {
+        Ok(Coverage::new_in(data::layers(), &get_all_pages()?))
+    }
+
+    /// Coverage of the languages in `layers`, with the prayers of `pages`
+    pub fn new_in(layers: &Layers, pages: &[Option<PrayerSetConfig>]) -> Coverage {
+        let mut corpus = Corpus::default();
+        let languages: Vec<Language> = corpus
+            .languages(layers)
+            .iter()
+            .map(|(dir, _)| Language::new(dir))
+            .collect();
+        let mut files = vec![];
+        for prayer in RosaryPrayer::ALL.iter().filter(|p| !p.is_mystery()) {
+            let file = Prayer::get_file(prayer);
+            if !file.is_empty() && !files.contains(&file) {
+                files.push(file);
+            }
+        }
+        for page in pages.iter().flatten() {
+            for (prayer, _) in referenced_prayers(page) {
+                if !files.contains(&prayer) {
+                    files.push(prayer);
+                }
+            }
+        }
+        // prayers no page uses yet
+        for prayer in files_in(layers, &languages, "*") {
+            if !files.contains(&prayer) {
+                files.push(prayer);
+            }
+        }
+        let mysteries: Vec<String> =
+            files_in(layers, &languages, &(String::from(MYSTERY_DIR) + "/*"))
+                .into_iter()
+                .collect();
+        Coverage::of(layers, &mut corpus, languages, &files, &mysteries)
+    }
+
+    /// Coverage of `files`, which need a title, and of `mysteries`, which need one if
+    /// any language has one
+    pub fn of(
+        layers: &Layers,
+        corpus: &mut Corpus,
+        languages: Vec<Language>,
+        files: &[String],
+        mysteries: &[String],
+    ) -> Coverage {
+        let mut has_title = |lan: &Language, file: &str| {
+            matches!(corpus.title(layers, &lan.to_string(), file), Some(Some(_)))
+        };
+        let mut needed = vec![];
+        for file in files {
+            needed.push((file, true));
+        }
+        for file in mysteries {
+            needed.push((file, languages.iter().any(|lan| has_title(lan, file))));
+        }
+        let mut coverage = vec![];
+        for (file, needs_title) in needed {
+            let mut presence = vec![];
+            for lan in &languages {
+                presence.push(Presence {
+                    text: layers.exists(&(lan.to_string() + "/" + file)),
+                    title: if needs_title {
+                        Some(has_title(lan, file))
+                    } else {
+                        None
+                    },
+                    audio: layers.find(&audio_path(lan, file)).is_some(),
+                });
+            }
+            coverage.push(FileCoverage {
+                file: file.clone(),
+                presence,
+            });
+        }
+        Coverage {
+            languages,
+            files: coverage,
+        }
+    }
+
+    /// Missing texts and titles, e.g. "anglia: mysteria/initium missing"
+    pub fn gaps(&self) -> Vec<String> {
+        let mut gaps = vec![];
+        for file in &self.files {
+            for (lan, presence) in self.languages.iter().zip(&file.presence) {
+                if !presence.text {
+                    gaps.push(format!("{}: {} missing", lan, file.file));
+                } else if presence.title == Some(false) {
+                    gaps.push(format!("{}: no title for \"{}\"", lan, file.file));
+                }
+            }
+        }
+        gaps
+    }
+}
+
+/// Files matching `pattern` in the directory of any of `languages`, relative to it
+fn files_in(layers: &Layers, languages: &[Language], pattern: &str) -> BTreeSet<String> {
+    let mut files = BTreeSet::new();
+    for lan in languages {
+        let pattern = lan.to_string() + "/" + pattern;
+        for layer in layers.iter() {
+            files.extend(
+                layer
+                    .glob(&pattern)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|f| layer.exists(f))
+                    .filter_map(|f| f.split_once('/').map(|(_, file)| file.to_string()))
+                    .filter(|file| !file.rsplit('/').next().unwrap_or(file).starts_with('.')),
+            );
+        }
+    }
+    files
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .files
+            .iter()
+            .map(|file| file.file.width())
+            .max()
+            .unwrap_or(0);
+        let mut header = format!("{:width$}", "", width = width);
+        for lan in &self.languages {
+            header += &format!("  {}", lan);
+        }
+        writeln!(f, "{}", header.trim_end())?;
+        for file in &self.files {
+            let mut line = format!("{:width$}", file.file, width = width);
+            for (lan, presence) in self.languages.iter().zip(&file.presence) {
+                line += &format!(
+                    "  {:lan_width$}",
+                    presence.abbreviation(),
+                    lan_width = lan.to_string().width()
+                );
+            }
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        write!(f, "T text, t title, a audio, - missing")
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[cfg(debug_assertions)]
+use crate::config::PRAYER_DIR;
+use crate::config::{CONFIG_FILE, DATA_DIR};
+use crate::tui::R;
+
+static LAYERS: OnceLock<Layers> = OnceLock::new();
+
+#[cfg(feature = "embed")]
+static PRECES: include_dir::Dir = include_dir::include_dir!("$CARGO_MANIFEST_DIR/preces");
+
+/// A source of prayer data, with paths relative to its root and separated by '/'
+pub trait Vfs: Send + Sync {
+    fn read(&self, path: &str) -> io::Result<String>;
+
+    fn exists(&self, path: &str) -> bool;
+
+    /// Files matching the glob `pattern`, sorted
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, String>;
+
+    /// Name of the file at `path` in messages
+    fn describe(&self, path: &str) -> String {
+        path.to_string()
+    }
+
+    /// Directory on disk the files are read from, if any
+    fn root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// Files in a directory on disk
+pub struct Dir(PathBuf);
+
+impl Dir {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Dir(root.into())
+    }
+}
+
+impl Vfs for Dir {
+    fn read(&self, path: &str) -> io::Result<String> {
+        fs::read_to_string(self.0.join(path))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.0.join(path).is_file()
+    }
+
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, String> {
+        let full_pattern = self.0.join(pattern);
+        let matches = glob::glob(&full_pattern.to_string_lossy()).map_err(|e| e.to_string())?;
+        let mut files = vec![];
+        for path in matches {
+            let path = path.map_err(|e| e.to_string())?;
+            if let Ok(relative) = path.strip_prefix(&self.0) {
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+        Ok(files)
+    }
+
+    fn describe(&self, path: &str) -> String {
+        self.0.join(path).display().to_string()
+    }
+
+    fn root(&self) -> Option<&Path> {
+        Some(&self.0)
+    }
+}
+
+/// Files held in memory, e.g. fixtures in tests
+#[derive(Default)]
+pub struct Memory(HashMap<String, String>);
+
+impl Memory {
+    pub fn new<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(files: I) -> Self {
+        Memory(
+            files
+                .into_iter()
+                .map(|(path, text)| (path.to_string(), text.to_string()))
+                .collect(),
+        )
+    }
+}
+
+impl Vfs for Memory {
+    fn read(&self, path: &str) -> io::Result<String> {
+        self.0.get(path).cloned().ok_or_else(|| not_found(path))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.0.contains_key(path)
+    }
+
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, String> {
+        glob_files(self.0.keys().map(String::as_str), pattern)
+    }
+}
+
+/// The prayer data compiled into the executable
+#[cfg(feature = "embed")]
+pub struct Embedded;
+
+#[cfg(feature = "embed")]
+impl Embedded {
+    fn files(dir: &'static include_dir::Dir<'static>, files: &mut Vec<&'static str>) {
+        files.extend(dir.files().filter_map(|f| f.path().to_str()));
+        for dir in dir.dirs() {
+            Embedded::files(dir, files);
+        }
+    }
+}
+
+#[cfg(feature = "embed")]
+impl Vfs for Embedded {
+    fn read(&self, path: &str) -> io::Result<String> {
+        let file = PRECES.get_file(path).ok_or_else(|| not_found(path))?;
+        file.contents_utf8().map(String::from).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{} is not UTF-8", path))
+        })
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        PRECES.get_file(path).is_some()
+    }
+
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, String> {
+        let mut files = vec![];
+        Embedded::files(&PRECES, &mut files);
+        glob_files(files.into_iter(), pattern)
+    }
+
+    fn describe(&self, path: &str) -> String {
+        format!("{} (embedded)", path)
+    }
+}
+
+fn glob_files<'a, I: Iterator<Item = &'a str>>(
+    files: I,
+    pattern: &str,
+) -> Result<Vec<String>, String> {
+    let pattern = glob::Pattern::new(pattern).map_err(|e| e.to_string())?;
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+        ..Default::default()
+    };
+    let mut files: Vec<String> = files
+        .filter(|f| pattern.matches_with(f, options))
+        .map(String::from)
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn not_found(path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} not found in any data directory", path),
+    )
+}
+
+/// Sources of prayer data by precedence, a file in an earlier one overrides the same
+/// file in later ones
+pub struct Layers(Vec<Box<dyn Vfs>>);
+
+impl Layers {
+    pub fn new(layers: Vec<Box<dyn Vfs>>) -> Self {
+        Layers(layers)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &dyn Vfs> {
+        self.0.iter().map(|layer| layer.as_ref())
+    }
+
+    /// Read the file at `path` from the first layer containing it
+    pub fn read(&self, path: &str) -> io::Result<String> {
+        match self.iter().find(|layer| layer.exists(path)) {
+            Some(layer) => layer.read(path),
+            None => Err(not_found(path)),
+        }
+    }
+
+    /// Read the file at `path` from every layer containing it, by precedence
+    pub fn read_all(&self, path: &str) -> Vec<String> {
+        self.iter()
+            .filter(|layer| layer.exists(path))
+            .filter_map(|layer| layer.read(path).ok())
+            .collect()
+    }
+
+    pub fn exists(&self, path: &str) -> bool {
+        self.iter().any(|layer| layer.exists(path))
+    }
+
+    /// Directories of the layers on disk, by precedence, without the embedded data
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.iter()
+            .filter_map(|layer| layer.root())
+            .map(Path::to_path_buf)
+            .collect()
+    }
+
+    /// The file at `path` on disk, if the first layer containing it is a directory
+    pub fn find(&self, path: &str) -> Option<PathBuf> {
+        let layer = self.iter().find(|layer| layer.exists(path))?;
+        layer.root().map(|root| root.join(path))
+    }
+}
+
+/// Set the data directories with `data_dir` taking precedence, before any data is read.
+/// Fails if none of them holds prayer sets
+pub fn init(data_dir: Option<PathBuf>) -> R {
+    if let Some(dir) = data_dir.as_ref().filter(|dir| !dir.is_dir()) {
+        return Err(format!("Data directory {} not found", dir.display()).into());
+    }
+    let layers = default_layers(data_dir.clone());
+    if !layers.exists(CONFIG_FILE) {
+        let searched: Vec<String> = searched_roots(data_dir)
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect();
+        return Err(format!(
+            "No prayer data found in {}, pass --data-dir or build with --features embed",
+            searched.join(", ")
+        )
+        .into());
+    }
+    LAYERS
+        .set(layers)
+        .map_err(|_| "Data directories are already set".into())
+}
+
+/// The data directories followed by the embedded data, if compiled in
+fn default_layers(data_dir: Option<PathBuf>) -> Layers {
+    #[allow(unused_mut)]
+    let mut layers: Vec<Box<dyn Vfs>> = data_roots(data_dir)
+        .into_iter()
+        .map(|root| Box::new(Dir::new(root)) as Box<dyn Vfs>)
+        .collect();
+    #[cfg(feature = "embed")]
+    layers.push(Box::new(Embedded));
+    Layers::new(layers)
+}
+
+/// Existing data directories by precedence: `data_dir`, ROSARIUM_DATA, XDG_DATA_HOME,
+/// XDG_DATA_DIRS and, in debug builds, the data in the sources
+pub fn data_roots(data_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut roots = searched_roots(data_dir);
+    let mut canonical = vec![];
+    roots.retain(|root| match root.canonicalize() {
+        Ok(path) if path.is_dir() && !canonical.contains(&path) => {
+            canonical.push(path);
+            true
+        }
+        _ => false,
+    });
+    roots
+}
+
+/// Data directories looked up by precedence, whether they exist or not
+fn searched_roots(data_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = data_dir.into_iter().collect();
+    if let Some(dirs) = env::var_os("ROSARIUM_DATA") {
+        roots.extend(env::split_paths(&dirs));
+    }
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    roots.extend(data_home.map(|dir| dir.join(DATA_DIR)));
+    let data_dirs = env::var_os("XDG_DATA_DIRS")
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+    roots.extend(
+        env::split_paths(&data_dirs)
+            .filter(|p| p.is_absolute())
+            .map(|dir| dir.join(DATA_DIR)),
+    );
+    #[cfg(debug_assertions)]
+    roots.push(PathBuf::from(PRAYER_DIR));
+    roots
+}
+
+pub fn layers() -> &'static Layers {
+    LAYERS.get_or_init(|| default_layers(None))
+}
+
+/// Read the file at `path` from the first layer containing it
+pub fn read(path: &str) -> io::Result<String> {
+    layers().read(path)
+}
+
+/// Read the file at `path` from every layer containing it, by precedence
+pub fn read_all(path: &str) -> Vec<String> {
+    layers().read_all(path)
+}
+
+/// Whether any layer contains `path`
+pub fn exists(path: &str) -> bool {
+    layers().exists(path)
+}
+
+/// The file at `path` on disk, from the first layer containing it
+pub fn find(path: &str) -> Option<PathBuf> {
+    layers().find(path)
+}
@@ -0,0 +1,117 @@
+//! Display of right-to-left scripts in terminals, which lay out every line from left to
+//! right
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::markup::{linea, Continuatio, RUBRICA_END, RUBRICA_START};
+use crate::orthographia::{STRESS_END, STRESS_START};
+use crate::tui_util::line_width;
+
+/// ISO 15924 codes of the scripts written from right to left
+pub const RTL_SCRIPTS: [&str; 8] = [
+    "Arab", "Hebr", "Syrc", "Thaa", "Nkoo", "Samr", "Mand", "Adlm",
+];
+
+pub fn is_rtl(script: &str) -> bool {
+    RTL_SCRIPTS.contains(&script)
+}
+
+/// Pairs of characters replaced by each other in right-to-left text, markup included so
+/// that it still opens before it closes
+const MIRRORED: [(char, char); 8] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('«', '»'),
+    ('‹', '›'),
+    (RUBRICA_START, RUBRICA_END),
+    (STRESS_START, STRESS_END),
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Directio {
+    /// Left to right
+    Dextrorsum,
+    /// Right to left
+    Sinistrorsum,
+    /// Spaces and punctuation, which take the direction of their surroundings
+    Neutra,
+}
+
+/// Direction of a grapheme cluster by its base character. Digits, also Arabic ones,
+/// are written from left to right
+fn directio(grapheme: &str) -> Directio {
+    let c = grapheme.chars().next().unwrap_or(' ');
+    match c {
+        '\u{0660}'..='\u{0669}' | '\u{06F0}'..='\u{06F9}' => Directio::Dextrorsum,
+        '\u{0590}'..='\u{08FF}'
+        | '\u{FB1D}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}'
+        | '\u{1E900}'..='\u{1E95F}' => Directio::Sinistrorsum,
+        c if c.is_alphanumeric() => Directio::Dextrorsum,
+        _ => Directio::Neutra,
+    }
+}
+
+fn mirror(grapheme: &str) -> String {
+    grapheme
+        .chars()
+        .map(|c| {
+            MIRRORED
+                .iter()
+                .find_map(|&(a, b)| {
+                    if c == a {
+                        Some(b)
+                    } else if c == b {
+                        Some(a)
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+/// Right-to-left `text` in the order it is displayed in, each line right aligned. Runs
+/// of left-to-right words and numbers keep their order, combining marks stay with their
+/// letters. Lines are expected to fit the window, wrapping breaks them in the wrong place
+pub fn visual(text: &str) -> String {
+    let lines: Vec<String> = text.lines().map(visual_line).collect();
+    let width = lines.iter().map(|line| line_width(line)).max().unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| " ".repeat(width - line_width(line)) + line)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// `line` in visual order, after the sign of a versicle or response or other markup
+/// beginning it
+fn visual_line(line: &str) -> String {
+    let text = linea(line, &mut Continuatio::default()).text;
+    let markup = &line[..line.len() - text.len()];
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let mut units: Vec<String> = vec![];
+    let mut i = 0;
+    while i < graphemes.len() {
+        if directio(graphemes[i]) != Directio::Dextrorsum {
+            units.push(mirror(graphemes[i]));
+            i += 1;
+            continue;
+        }
+        // a left-to-right run up to its last left-to-right grapheme
+        let mut end = i;
+        for (j, g) in graphemes.iter().enumerate().skip(i) {
+            match directio(g) {
+                Directio::Dextrorsum => end = j,
+                Directio::Sinistrorsum => break,
+                Directio::Neutra => {}
+            }
+        }
+        units.push(graphemes[i..=end].concat());
+        i = end + 1;
+    }
+    markup.to_string() + &units.into_iter().rev().collect::<String>()
+}
@@ -1,4 +1,5 @@
 use crate::{
+    language::{get_ui_translation, Language},
     render::{redraw, refresh},
     tui::{Frame, MenuItem, E},
 };
@@ -8,40 +9,88 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use std::error::Error;
 use std::io::Stdout;
 
-pub fn get_keybindings(f: &Frame) -> String {
-    let mut keybinds = String::from(
-        "?:   Toggle Keybindings
-r:   Refresh window
-q:   Quit
+/// Keys and the interface strings describing them, in groups
+const KEYBINDINGS: [&[(&str, &str)]; 3] = [
+    &[
+        ("?", "claves/claves"),
+        ("r", "claves/renovare"),
+        ("q", "claves/exire"),
+    ],
+    &[("j", "claves/sursum"), ("k", "claves/deorsum")],
+    &[
+        ("x", "claves/lingua"),
+        ("b", "claves/bilinguis"),
+        ("X", "claves/translatio"),
+        ("o", "claves/scriptura"),
+        ("a", "claves/orthographia"),
+        ("A", "claves/accentus"),
+        ("c", "claves/nexus"),
+        ("w", "claves/fenestra_sequens"),
+    ],
+];
 
-j:   Scroll up
-k:   Scroll down
+const AUDIO_KEYBINDINGS: [&[(&str, &str)]; 2] = [
+    &[
+        ("p", "claves/sonus"),
+        ("v", "claves/volumen"),
+        ("h", "claves/volumen_minuere"),
+        ("l", "claves/volumen_augere"),
+        ("1-9", "claves/volumen_ponere"),
+    ],
+    &[
+        ("H", "claves/dividere_horizontaliter"),
+        ("L", "claves/dividere_verticaliter"),
+    ],
+];
 
-x:   Cycle language
-",
-    );
-    keybinds += match f.get_active_window_ro().active_menu_item() {
-        MenuItem::Rosary => "\nSpace/l/Right: Advance Rosary\nBackspace/h/Left: Recede Rosary",
-        MenuItem::PrayerSet(_) => {
-            "\nSpace/l/Right: Advance Prayer\nBackspace/h/Left: Recede Prayer"
-        }
-        _ => "",
-    };
-    keybinds += "\n
-p:   Play/Pause audio (if available for current window)
-v:   Toggle Volume Popup
-h:   Lower Volume (when volume popup active)
-l:   Raise Volume (when volume popup active)
-1-9: Set Volume to 10-90% (0 sets to 100%)
+fn keybinding_group(group: &[(&str, &str)], lan: &Language) -> String {
+    group
+        .iter()
+        .map(|(keys, key)| {
+            format!(
+                "{:<4} {}\n",
+                keys.to_string() + ":",
+                get_ui_translation(key, lan)
+            )
+        })
+        .collect()
+}
 
-H:   Split window horizontal
-L:   Split window vertical
-";
-    keybinds
+pub fn get_keybindings(f: &Frame) -> String {
+    let window = f.get_active_window_ro();
+    let lan = window.get_language();
+    let item: &[(&str, &str)] = match window.active_menu_item() {
+        MenuItem::Rosary => &[
+            ("Space/l/Right", "claves/rosarium_progredi"),
+            ("Backspace/h/Left", "claves/rosarium_regredi"),
+        ],
+        MenuItem::PrayerSet(_) => &[
+            ("Space/l/Right", "claves/oratio_progredi"),
+            ("Backspace/h/Left", "claves/oratio_regredi"),
+            ("s", "claves/miscere"),
+        ],
+        MenuItem::Calendar => &[
+            ("l/Right", "claves/dies_sequens"),
+            ("h/Left", "claves/dies_prior"),
+            ("t", "claves/hodie"),
+            ("f", "claves/disciplina"),
+            ("F", "claves/feriae_sextae"),
+            ("g", "claves/regio"),
+        ],
+        _ => &[],
+    };
+    KEYBINDINGS
+        .iter()
+        .chain(std::iter::once(&item))
+        .chain(AUDIO_KEYBINDINGS.iter())
+        .filter(|group| !group.is_empty())
+        .map(|group| keybinding_group(group, lan))
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
-pub fn general_input_handler<'a>(
-    terminal: &'a mut Terminal<CrosstermBackend<Stdout>>,
+pub fn general_input_handler(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     mut frame: Frame,
     event: &KeyEvent,
 ) -> (Frame, Result<MenuItem, E>) {
@@ -49,15 +98,17 @@ pub fn general_input_handler<'a>(
         KeyCode::Char('q') => {
             let a = disable_raw_mode();
             let b = terminal.show_cursor();
-            if a.is_err() || b.is_err() {
-                return (frame, Err(Box::new(a.unwrap_err())));
+            if let Err(err) = a {
+                return (frame, Err(Box::new(err)));
+            }
+            if let Err(err) = b {
+                return (frame, Err(Box::new(err)));
             }
             return (frame, Ok(MenuItem::Quit));
         }
         KeyCode::Char('r') => {
-            let a = refresh(terminal, &mut frame);
-            if a.is_err() {
-                return (frame, Err(a.unwrap_err()));
+            if let Err(err) = refresh(terminal, &mut frame) {
+                return (frame, Err(err));
             }
         }
 
@@ -65,33 +116,38 @@ pub fn general_input_handler<'a>(
         KeyCode::Char('k') => frame.get_active_window().down(),
         KeyCode::Char('j') => frame.get_active_window().up(),
         KeyCode::Char('x') => frame.get_active_window().cycle_language(),
+        KeyCode::Char('b') => frame.get_active_window().cycle_bilingual(),
+        KeyCode::Char('X') => frame.get_active_window().cycle_translation(),
+        KeyCode::Char('o') => frame.get_active_window().cycle_scriptura(),
+        KeyCode::Char('a') => frame.get_active_window().cycle_orthographia(),
+        KeyCode::Char('A') => frame.get_active_window().toggle_accentus(),
+        KeyCode::Char('c') => frame.toggle_link(),
+        KeyCode::Char('w') => frame.focus_next(),
         KeyCode::Char('v') => frame.toggle_volume_popup(),
         KeyCode::Char('?') => frame.toggle_keybinding_popup(),
         KeyCode::Char('H') => {
             let (f, error) = frame.hsplit();
             frame = f;
-            if error.is_err() {
-                return (frame, Err(error.unwrap_err()));
+            if let Err(err) = error {
+                return (frame, Err(err));
             }
         }
         KeyCode::Char('L') => {
             let (f, error) = frame.vsplit();
             frame = f;
-            if error.is_err() {
-                return (frame, Err(error.unwrap_err()));
+            if let Err(err) = error {
+                return (frame, Err(err));
             }
         }
         KeyCode::Char('p') => {
-            let error = frame.toggle_audio();
-            if error.is_err() {
-                return (frame, Err(error.unwrap_err()));
+            if let Err(err) = frame.toggle_audio() {
+                return (frame, Err(err));
             }
         }
         _ => return (frame, Ok(MenuItem::_NOQUIT)),
     }
-    let a = redraw(terminal, &mut frame);
-    if a.is_err() {
-        return (frame, Err(a.unwrap_err()));
+    if let Err(err) = redraw(terminal, &mut frame) {
+        return (frame, Err(err));
     }
     let active_menu_item = frame.get_active_window().active_menu_item();
     (frame, Ok(active_menu_item))
@@ -116,7 +172,7 @@ pub fn volume_input_handler<'a>(
         KeyCode::Char('8') => frame.set_volume(80),
         KeyCode::Char('9') => frame.set_volume(90),
         KeyCode::Char('0') => frame.set_volume(100),
-        _ => Ok({}),
+        _ => Ok(()),
     }?;
     redraw(terminal, frame)?;
     Ok(Some(frame.get_active_window_ro().active_menu_item()))
@@ -136,6 +192,7 @@ pub fn rosary_input_handler<'a>(
         KeyCode::Backspace => frame.get_active_window().rosary.recede(),
         _ => {}
     }
+    frame.sync_linked();
     redraw(terminal, frame)?;
     Ok(frame.get_active_window().active_menu_item())
 }
@@ -151,6 +208,9 @@ pub fn calendar_input_handler<'a>(
         KeyCode::Left => frame.get_active_window().left(),
         KeyCode::Right => frame.get_active_window().right(),
         KeyCode::Char('t') => frame.get_active_window().reset_horizontal_scroll(),
+        KeyCode::Char('f') => frame.get_active_window().cycle_disciplina(),
+        KeyCode::Char('F') => frame.get_active_window().toggle_omnes_feriae_sextae(),
+        KeyCode::Char('g') => frame.get_active_window().cycle_regio()?,
         _ => {}
     }
     redraw(terminal, frame)?;
@@ -169,6 +229,7 @@ pub fn prayer_set_input_handler<'a>(
         KeyCode::Left => frame.get_active_window().get_curr_prayer_set()?.recede(),
         KeyCode::Right => frame.get_active_window().get_curr_prayer_set()?.advance(),
         KeyCode::Backspace => frame.get_active_window().get_curr_prayer_set()?.recede(),
+        KeyCode::Char('s') => frame.get_active_window().reshuffle()?,
         _ => {}
     }
     redraw(terminal, frame)?;
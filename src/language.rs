@@ -1,31 +1,239 @@
-use crate::config::{PRAYER_DIR, TITLE_FILE};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::config::{DEFAULT_LANGUAGE, TITLE_FILE};
+use crate::corpus::{self, Corpus};
+use crate::data::Layers;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::fmt;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub enum Language {
-    LATINA,
-    GERMANA,
-    SLAVONICA,
-    ANGLIA,
-}
+/// Namespace of the strings of the interface in the .tituli files
+pub const UI_NAMESPACE: &str = "ui/";
+
+/// Namespace of the ordinals filled into phrases, below `UI_NAMESPACE`
+pub const ORDINAL_NAMESPACE: &str = "ordinalis/";
+
+/// Keys of the strings of the interface, without the namespace
+pub const UI_KEYS: [&str; 79] = [
+    "oratio",
+    "mysteria_rosarii",
+    "volumen",
+    "claves",
+    "error",
+    "calendarium",
+    "dies",
+    "nomen",
+    "hodie",
+    "translationes",
+    "legenda",
+    "dominica",
+    "feria_secunda",
+    "feria_tertia",
+    "feria_quarta",
+    "feria_quinta",
+    "feria_sexta",
+    "sabbatum",
+    "semen",
+    "dies_praecepti",
+    "poenitentia/ieiunium",
+    "poenitentia/abstinentia",
+    "poenitentia/ieiunium_et_abstinentia",
+    "causa/dies_cinerum",
+    "causa/dies_passionis_domini",
+    "causa/quadragesima",
+    "causa/feria_sexta",
+    "causa/feria_quarta",
+    "causa/quattuor_tempora",
+    "causa/vigilia",
+    "causa/dies_ieiunii_stricti",
+    "causa/magna_quadragesima",
+    "causa/hebdomas_casei",
+    "causa/ieiunium_apostolorum",
+    "causa/ieiunium_dormitionis",
+    "causa/ieiunium_nativitatis",
+    "disciplina_hodierna",
+    "disciplina_antiqua",
+    "disciplina_orientalis",
+    "claves/claves",
+    "claves/renovare",
+    "claves/exire",
+    "claves/sursum",
+    "claves/deorsum",
+    "claves/lingua",
+    "claves/bilinguis",
+    "claves/translatio",
+    "claves/scriptura",
+    "claves/orthographia",
+    "claves/accentus",
+    "claves/nexus",
+    "claves/fenestra_sequens",
+    "claves/rosarium_progredi",
+    "claves/rosarium_regredi",
+    "claves/oratio_progredi",
+    "claves/oratio_regredi",
+    "claves/miscere",
+    "claves/dies_sequens",
+    "claves/dies_prior",
+    "claves/hodie",
+    "claves/disciplina",
+    "claves/feriae_sextae",
+    "claves/regio",
+    "claves/sonus",
+    "claves/volumen",
+    "claves/volumen_minuere",
+    "claves/volumen_augere",
+    "claves/volumen_ponere",
+    "claves/dividere_horizontaliter",
+    "claves/dividere_verticaliter",
+    "positio/crucifixum",
+    "positio/nodus",
+    "positio/tergeminum",
+    "positio/granum",
+    "positio/ante_decennium",
+    "positio/post_decennium",
+    "positio/finis",
+    "positio/decennium",
+    "positio/mysterium",
+];
+
+/// A language of prayers, i.e. a subdirectory of the data directories with a .lingua file
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Language(String);
 
 impl Language {
-    pub const VALUES: [Self; 4] = [Self::ANGLIA, Self::GERMANA, Self::LATINA, Self::SLAVONICA];
+    /// Language in the subdirectory `dir`
+    pub fn new(dir: &str) -> Self {
+        Language(dir.to_string())
+    }
+
+    /// All languages found in the data directories, sorted by directory
+    pub fn all() -> Vec<Language> {
+        corpus::languages()
+            .into_iter()
+            .map(|(dir, _)| Language(dir))
+            .collect()
+    }
+
+    /// The language windows start with
+    pub fn initial() -> Language {
+        Language::initial_in(&corpus::languages())
+    }
+
+    /// The language windows start with among `languages`
+    pub fn initial_in(languages: &[(String, LanguageInfo)]) -> Language {
+        languages
+            .iter()
+            .find(|(dir, _)| dir == DEFAULT_LANGUAGE)
+            .or(languages.first())
+            .map_or_else(
+                || Language::new(DEFAULT_LANGUAGE),
+                |(dir, _)| Language::new(dir),
+            )
+    }
+
+    pub fn info(&self) -> LanguageInfo {
+        corpus::languages()
+            .into_iter()
+            .find(|(dir, _)| *dir == self.0)
+            .map_or_else(|| LanguageInfo::unknown(&self.0), |(_, info)| info)
+    }
+
+    /// Name of the language in itself
+    pub fn name(&self) -> String {
+        self.info().name
+    }
+
+    /// Languages to take prayers from when missing in this one, in order
+    pub fn fallbacks(&self) -> Vec<Language> {
+        fallback_chain(&self.0, &corpus::languages())
+            .into_iter()
+            .map(Language)
+            .collect()
+    }
+
+    /// This language followed by its fallbacks
+    pub fn chain(&self) -> Vec<Language> {
+        let mut chain = vec![self.clone()];
+        chain.append(&mut self.fallbacks());
+        chain
+    }
+
+    /// The next language when cycling through all of them
+    pub fn next(&self) -> Language {
+        let languages = Language::all();
+        let next = languages
+            .iter()
+            .position(|l| l == self)
+            .map_or(0, |i| i + 1);
+        languages
+            .get(next % languages.len().max(1))
+            .cloned()
+            .unwrap_or_else(|| self.clone())
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Contents of the .lingua file of a language
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LanguageInfo {
+    /// Name of the language in itself
+    pub name: String,
+    /// BCP 47 language tag, e.g. "la"
+    pub code: String,
+    /// ISO 15924 script code, e.g. "Latn"
+    #[serde(default = "default_script")]
+    pub script: String,
+    /// Directories of the languages to take missing prayers from, in order
+    #[serde(default)]
+    pub fallback: Vec<String>,
 }
 
-impl ToString for Language {
-    fn to_string(&self) -> String {
-        match self {
-            Language::LATINA => "latina",
-            Language::GERMANA => "germana",
-            Language::ANGLIA => "anglia",
-            Language::SLAVONICA => "slavonica_antiqua",
+fn default_script() -> String {
+    String::from("Latn")
+}
+
+impl LanguageInfo {
+    pub fn parse(s: &str) -> Result<LanguageInfo, serde_yaml::Error> {
+        serde_yaml::from_str(s)
+    }
+
+    /// Stands in for a missing or invalid .lingua file
+    pub fn unknown(dir: &str) -> LanguageInfo {
+        LanguageInfo {
+            name: dir.to_string(),
+            code: String::from("und"),
+            script: default_script(),
+            fallback: vec![],
         }
-        .to_owned()
     }
 }
 
+/// The fallbacks declared for `lan`, then those declared for them and so on, without
+/// repetitions and languages that do not exist
+pub fn fallback_chain(lan: &str, languages: &[(String, LanguageInfo)]) -> Vec<String> {
+    let declared = |lan: &str| {
+        languages
+            .iter()
+            .find(|(dir, _)| dir == lan)
+            .map(|(_, info)| info.fallback.clone())
+            .unwrap_or_default()
+    };
+    let mut chain: Vec<String> = vec![];
+    let mut queue: VecDeque<String> = declared(lan).into();
+    while let Some(dir) = queue.pop_front() {
+        let exists = languages.iter().any(|(d, _)| *d == dir);
+        if exists && dir != lan && !chain.contains(&dir) {
+            queue.extend(declared(&dir));
+            chain.push(dir);
+        }
+    }
+    chain
+}
+
 /// Ordinals for Latin neuter accusative singular
 pub fn ordinal_n_acc(i: u8) -> &'static str {
     match i {
@@ -65,22 +273,128 @@ pub fn ordinal_n_gen(i: u8) -> &'static str {
     }
 }
 
+/// Title of `lookup` in `lan`, or else in the first of its fallback languages having it
 pub fn get_title_translation(lookup: &str, lan: &Language) -> String {
-    let filename = PRAYER_DIR.to_owned() + "/" + &lan.to_string() + "/" + TITLE_FILE;
-    // Open the file in read-only mode (ignoring errors).
-    let file = File::open(&filename);
-    if file.is_err() {
-        return format!("Unable to open title file: {}", &filename);
-    }
-    let file = file.unwrap();
-    let reader = BufReader::new(file);
-
-    // Read the file line by line using the lines() iterator from std::io::BufRead.
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.expect("Error fetching line"); // Ignore errors.
-        if line.starts_with(&(String::from(lookup) + ":")) {
-            return String::from(line.split(":").nth(1).unwrap_or("no title found").trim());
+    let mut title_file = false;
+    for lan in lan.chain() {
+        match corpus::title(&lan.to_string(), lookup) {
+            Some(Some(title)) => return title,
+            Some(None) => title_file = true,
+            None => {}
+        }
+    }
+    if title_file {
+        format!("No title found for prayer {}", lookup)
+    } else {
+        format!(
+            "Unable to open title file: {}",
+            lan.to_string() + "/" + TITLE_FILE
+        )
+    }
+}
+
+/// String `key` of the interface in `lan`, or else in the first of its fallback languages
+/// having it, or else the key itself
+pub fn get_ui_translation(key: &str, lan: &Language) -> String {
+    corpus::with(|corpus, layers| get_ui_translation_in(corpus, layers, key, lan))
+}
+
+/// `get_ui_translation` on the titles of `layers`
+pub fn get_ui_translation_in(
+    corpus: &mut Corpus,
+    layers: &Layers,
+    key: &str,
+    lan: &Language,
+) -> String {
+    let lookup = String::from(UI_NAMESPACE) + key;
+    chain_in(corpus, layers, lan)
+        .into_iter()
+        .find_map(|dir| corpus.title(layers, &dir, &lookup).flatten())
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Phrase `key` of the interface in `lan`, or else in the first of its fallback languages
+/// having it, with its placeholders filled by ordinals of that language, see
+/// `fill_ordinals`. `None` if no language has the phrase
+pub fn get_ui_phrase(key: &str, numbers: &[u8], lan: &Language) -> Option<String> {
+    corpus::with(|corpus, layers| get_ui_phrase_in(corpus, layers, key, numbers, lan))
+}
+
+/// `get_ui_phrase` on the titles of `layers`
+pub fn get_ui_phrase_in(
+    corpus: &mut Corpus,
+    layers: &Layers,
+    key: &str,
+    numbers: &[u8],
+    lan: &Language,
+) -> Option<String> {
+    let lookup = String::from(UI_NAMESPACE) + key;
+    chain_in(corpus, layers, lan).into_iter().find_map(|dir| {
+        let phrase = corpus.title(layers, &dir, &lookup).flatten()?;
+        Some(fill_ordinals(&phrase, numbers, |form, n| {
+            let form = if form.is_empty() {
+                String::new()
+            } else {
+                form.to_string() + "/"
+            };
+            let ordinal = format!("{}{}{}{}", UI_NAMESPACE, ORDINAL_NAMESPACE, form, n);
+            corpus.title(layers, &dir, &ordinal).flatten()
+        }))
+    })
+}
+
+/// Directory of `lan` followed by those of its fallbacks among the languages of `layers`
+fn chain_in(corpus: &mut Corpus, layers: &Layers, lan: &Language) -> Vec<String> {
+    let mut chain = vec![lan.to_string()];
+    chain.append(&mut fallback_chain(
+        &lan.to_string(),
+        corpus.languages(layers),
+    ));
+    chain
+}
+
+/// Replace the placeholders `{i}` and `{i:form}` in `phrase` by the ordinal of the i-th of
+/// `numbers` in the grammatical form `form`, e.g. "{1:n_acc}" by "primum". A trailing '^'
+/// as in "{1:n_acc^}" capitalizes the ordinal. Ordinals `ordinal` does not know are
+/// written as numbers
+pub fn fill_ordinals<F: FnMut(&str, u8) -> Option<String>>(
+    phrase: &str,
+    numbers: &[u8],
+    mut ordinal: F,
+) -> String {
+    let mut filled = String::new();
+    let mut rest = phrase;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        let placeholder = &rest[start + 1..end];
+        let (placeholder, upper) = match placeholder.strip_suffix('^') {
+            Some(placeholder) => (placeholder, true),
+            None => (placeholder, false),
+        };
+        let (index, form) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+        let number = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| numbers.get(i.checked_sub(1)?));
+        filled += &rest[..start];
+        match number {
+            Some(&n) => {
+                let word = ordinal(form, n).unwrap_or_else(|| n.to_string());
+                filled += &if upper { capitalize(&word) } else { word };
+            }
+            None => filled += &rest[start..=end],
         }
+        rest = &rest[end + 1..];
+    }
+    filled + rest
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
     }
-    return format!("No title found for prayer {}", lookup);
 }
@@ -3,37 +3,95 @@ extern crate core;
 
 pub mod audio;
 pub mod calender;
+pub mod check;
+pub mod cli;
 pub mod config;
 pub mod config_parse;
+pub mod corpus;
+pub mod coverage;
+pub mod data;
+pub mod directio;
 pub mod events;
 pub mod language;
+pub mod markup;
+pub mod orthographia;
 pub mod prayer;
 pub mod render;
 pub mod rosary;
+pub mod scriptura;
 pub mod tui;
 pub mod tui_util;
+pub mod watch;
 
 #[cfg(test)]
 mod tests {
+    use crate::calender::{
+        get_mystery_enum, load_regiones, AnnusLiturgicus, Disciplina, Poenitentia, Regio,
+    };
+    use crate::config::REGIONES_FILE;
+    use crate::config_parse::{
+        get_order, parse_pages, parse_prayer_sets, ConfigError, Count, OrderEntry,
+    };
+    use crate::data::{Layers, Memory};
+    use crate::rosary::Mysteries::{Glorious, Joyful, Sorrowful};
     use crate::rosary::Rosary;
+    use chrono::NaiveDate;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::sync::OnceLock;
+
+    /// The region named `name` as shipped, whatever the data directories of the user hold
+    fn regio(name: &str) -> &'static Regio {
+        static REGIONES: OnceLock<Vec<Regio>> = OnceLock::new();
+        REGIONES
+            .get_or_init(|| {
+                let layers = Layers::new(vec![Box::new(Memory::new([(
+                    REGIONES_FILE,
+                    include_str!("../preces/.regiones.yaml"),
+                )]))]);
+                load_regiones(&layers).unwrap()
+            })
+            .iter()
+            .find(|r| r.name == name)
+            .unwrap()
+    }
+
+    /// The languages and titles as shipped, whatever the data directories of the user hold
+    fn linguae() -> Layers {
+        Layers::new(vec![Box::new(Memory::new([
+            ("anglia/.lingua", include_str!("../preces/anglia/.lingua")),
+            ("anglia/.tituli", include_str!("../preces/anglia/.tituli")),
+            ("germana/.lingua", include_str!("../preces/germana/.lingua")),
+            ("germana/.tituli", include_str!("../preces/germana/.tituli")),
+            ("latina/.lingua", include_str!("../preces/latina/.lingua")),
+            ("latina/.tituli", include_str!("../preces/latina/.tituli")),
+            (
+                "slavonica_antiqua/.lingua",
+                include_str!("../preces/slavonica_antiqua/.lingua"),
+            ),
+            (
+                "slavonica_antiqua/.tituli",
+                include_str!("../preces/slavonica_antiqua/.tituli"),
+            ),
+        ]))])
+    }
 
     #[test]
     fn rosary_forwards() {
         let mut rosary = Rosary::new();
-        let mut rosary_forward: Vec<Rosary> = vec![rosary.clone()];
+        let mut rosary_forward: Vec<Rosary> = vec![rosary];
         let mut rosary_backward: Vec<Rosary> = vec![];
         loop {
             rosary.advance();
-            rosary_forward.push(rosary.clone());
+            rosary_forward.push(rosary);
 
             if rosary.get_decade() == 5 && rosary.get_bead() == 12 {
                 break;
             }
         }
-        rosary_backward.push(rosary.clone());
+        rosary_backward.push(rosary);
         loop {
             rosary.recede();
-            rosary_backward.push(rosary.clone());
+            rosary_backward.push(rosary);
 
             if rosary.get_decade() == 0 && rosary.get_bead() == 0 {
                 break;
@@ -42,4 +100,913 @@ mod tests {
         rosary_backward.reverse();
         assert_eq!(rosary_forward, rosary_backward);
     }
+
+    #[test]
+    fn seasonal_mysteries() {
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        // Sundays of Lent, from the first to Palm Sunday
+        assert_eq!(get_mystery_enum(day(2, 18)), Sorrowful);
+        assert_eq!(get_mystery_enum(day(3, 24)), Sorrowful);
+        // Sundays of Advent, from the first to the fourth
+        assert_eq!(get_mystery_enum(day(12, 1)), Joyful);
+        assert_eq!(get_mystery_enum(day(12, 22)), Joyful);
+        // Easter and Sundays outside these seasons keep the Glorious mysteries
+        assert_eq!(get_mystery_enum(day(3, 31)), Glorious);
+        assert_eq!(get_mystery_enum(day(2, 11)), Glorious);
+        assert_eq!(get_mystery_enum(day(11, 24)), Glorious);
+        assert_eq!(get_mystery_enum(day(12, 29)), Glorious);
+        // weekdays of Lent follow the weekday
+        assert_eq!(get_mystery_enum(day(2, 19)), Joyful);
+    }
+
+    #[test]
+    fn fast_days() {
+        let al = AnnusLiturgicus::new(2024, regio("ecclesia_universalis")).unwrap();
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        // Ash Wednesday and a Friday of Lent
+        assert_eq!(
+            al.ieiunium(day(2, 14), Disciplina::Hodierna, false),
+            Some((Poenitentia::IeiuniumEtAbstinentia, "dies_cinerum"))
+        );
+        assert_eq!(
+            al.ieiunium(day(2, 23), Disciplina::Hodierna, false),
+            Some((Poenitentia::Abstinentia, "quadragesima"))
+        );
+        assert_eq!(al.ieiunium(day(6, 7), Disciplina::Hodierna, false), None);
+        assert_eq!(
+            al.ieiunium(day(6, 7), Disciplina::Hodierna, true),
+            Some((Poenitentia::Abstinentia, "feria_sexta"))
+        );
+        // Ember Saturday after Pentecost
+        assert_eq!(
+            al.ieiunium(day(5, 25), Disciplina::Antiqua, false),
+            Some((Poenitentia::IeiuniumEtAbstinentia, "quattuor_tempora"))
+        );
+        // Orthodox Great Friday (Pascha on May 5) and Bright Friday
+        assert_eq!(
+            al.ieiunium(day(5, 3), Disciplina::Orientalis, false),
+            Some((Poenitentia::IeiuniumEtAbstinentia, "dies_ieiunii_stricti"))
+        );
+        assert_eq!(al.ieiunium(day(5, 10), Disciplina::Orientalis, false), None);
+    }
+
+    #[test]
+    fn solemnities_on_fridays_of_lent() {
+        // St Joseph on Friday 19 March 2021 and the Annunciation on Friday 25 March 2022
+        for (year, day) in [(2021, 19), (2022, 25)] {
+            let al = AnnusLiturgicus::new(year, regio("ecclesia_universalis")).unwrap();
+            let date = NaiveDate::from_ymd_opt(year, 3, day).unwrap();
+            assert_eq!(al.ieiunium(date, Disciplina::Hodierna, false), None);
+            assert_eq!(al.ieiunium(date, Disciplina::Hodierna, true), None);
+            // the Friday after is one of Lent again
+            assert_eq!(
+                al.ieiunium(
+                    date + chrono::Duration::weeks(1),
+                    Disciplina::Hodierna,
+                    false
+                ),
+                Some((Poenitentia::Abstinentia, "quadragesima"))
+            );
+        }
+    }
+
+    #[test]
+    fn holy_days_of_obligation() {
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let date_of =
+            |al: &AnnusLiturgicus, name| al.to_vec().iter().find(|(n, _)| *n == name).unwrap().1;
+        let germania = AnnusLiturgicus::new(2024, regio("germania")).unwrap();
+        assert_eq!(date_of(&germania, "ascensio_domini"), day(5, 9));
+        assert!(germania.dies_praecepti(day(5, 30)));
+        assert!(!germania.dies_praecepti(day(12, 9)));
+        // Epiphany, Ascension and Corpus Christi transferred to Sunday
+        let usa = AnnusLiturgicus::new(2024, regio("civitates_foederatae_americae")).unwrap();
+        assert_eq!(date_of(&usa, "epiphan_domini"), day(1, 7));
+        assert_eq!(date_of(&usa, "ascensio_domini"), day(5, 12));
+        assert_eq!(date_of(&usa, "corpus_christi"), day(6, 2));
+        assert!(!usa.dies_praecepti(day(5, 9)));
+        // no longer of obligation in England and Wales since 2017
+        let anglia = AnnusLiturgicus::new(2024, regio("anglia_et_cambria")).unwrap();
+        assert!(!anglia.dies_praecepti(day(1, 1)));
+    }
+
+    #[test]
+    fn layered_regions() {
+        use crate::calender::load_regiones;
+        let layers = Layers::new(vec![
+            Box::new(Memory::new([(
+                ".regiones.yaml",
+                "- name: b\n  ascensio_dominica: true\n- name: c\n",
+            )])),
+            Box::new(Memory::new([(
+                ".regiones.yaml",
+                "- name: a\n  dies_praecepti: [omnium_sanctorum]\n- name: b\n",
+            )])),
+        ]);
+        let regiones = load_regiones(&layers).unwrap();
+        let names: Vec<&str> = regiones.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert!(regiones[1].ascensio_dominica);
+        assert_eq!(regiones[0].dies_praecepti, ["omnium_sanctorum"]);
+        let unknown = Layers::new(vec![Box::new(Memory::new([(
+            ".regiones.yaml",
+            "- name: a\n  pentecostes_dominica: true\n",
+        )]))]);
+        assert!(load_regiones(&unknown).is_err());
+        assert!(load_regiones(&Layers::new(vec![])).is_err());
+        // the corpus keeps the regions, and an error reading them, until invalidated
+        let mut corpus = crate::corpus::Corpus::default();
+        assert!(corpus.regiones(&unknown).is_err());
+        assert!(corpus.regiones(&layers).is_err());
+        corpus = crate::corpus::Corpus::default();
+        assert_eq!(corpus.regiones(&layers).unwrap().len(), 3);
+        assert_eq!(corpus.regiones(&unknown).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn config_counts() {
+        let configs = parse_prayer_sets(
+            "title: a\norder:\n  - gloria_patri:\n      count: \"3\"\n  - ave_maria:\n      count: 1-2\n---\n",
+        )
+        .unwrap();
+        assert_eq!(configs.len(), 1);
+        let count = |i: usize| match &configs[0].order[i] {
+            OrderEntry::Group(g) => g[0].1.count,
+            _ => None,
+        };
+        assert_eq!(count(0), Some(Count { min: 3, max: 3 }));
+        assert_eq!(count(1), Some(Count { min: 1, max: 2 }));
+        for count in ["18446744073709551615", "\"1-1001\""] {
+            let err = parse_prayer_sets(&format!(
+                "title: a\norder:\n  - signum_crucis\n  - x:\n      count: {}\n",
+                count
+            ))
+            .unwrap_err();
+            assert!(matches!(
+                err,
+                ConfigError::Parse { order_index: Some(1), ref message, .. }
+                    if message.contains("at most 1000")
+            ));
+        }
+    }
+
+    #[test]
+    fn config_error_location() {
+        let err = parse_prayer_sets(
+            "title: a\n---\ntitle: b\norder:\n  - signum_crucis\n  - gloria_patri:\n      count: \"3-\"\n",
+        )
+        .unwrap_err();
+        match err {
+            ConfigError::Parse {
+                title,
+                page,
+                order_index,
+                location,
+                ..
+            } => {
+                assert_eq!(title.as_deref(), Some("b"));
+                assert_eq!(page, 2);
+                assert_eq!(order_index, Some(1));
+                assert_eq!(location, Some((7, 14)));
+            }
+            _ => panic!("expected parse error"),
+        }
+        assert!(parse_prayer_sets("title: a\norder:\n  - x:\n      chance: 150\n").is_err());
+        assert!(parse_prayer_sets("title: a\noder: []\n").is_err());
+        assert!(parse_prayer_sets("title: a\norder: [\n").is_err());
+    }
+
+    // checks the data in the sources, which only debug builds know of
+    #[cfg(debug_assertions)]
+    #[test]
+    fn check_corpus() {
+        use crate::check::{check_in, exit_code, Finding, Severity};
+        use crate::cli::Args;
+        use crate::config::PRAYER_DIR;
+        use crate::config_parse::layered_pages;
+        use crate::data::Dir;
+        let layers = Layers::new(vec![Box::new(Dir::new(PRAYER_DIR))]);
+        let findings = check_in(&layers, layered_pages(&layers));
+        // the shipped data has warnings, which only fail a strict check
+        assert_eq!(exit_code(&findings, false), 0);
+        assert_eq!(exit_code(&findings, true), 1);
+        assert_eq!(exit_code(&[], true), 0);
+        let error = Finding {
+            severity: Severity::Error,
+            message: String::new(),
+        };
+        assert_eq!(exit_code(&[error], false), 1);
+        let args = |a: &[&str]| Args::parse(a.iter().map(|s| s.to_string()));
+        assert!(args(&["check", "--strict"]).unwrap().strict);
+        assert!(args(&["--strict"]).is_err());
+        let messages: Vec<String> = findings.iter().map(|f| f.to_string()).collect();
+        assert!(messages.iter().any(|m| m.contains("is empty")));
+        assert!(messages
+            .iter()
+            .any(|m| m.contains("\"novena/infant_of_prague\" missing in latina")));
+    }
+
+    #[test]
+    fn conditional_prayers() {
+        let configs = parse_prayer_sets(
+            "title: a
+order:
+  - a:
+      when: {season: lent}
+  - b:
+      unless: {season: [easter, lent]}
+  - c:
+      when: {weekday: fri, month: [3, 7]}
+  - d:
+      when: {feast: pentecostes}
+",
+        )
+        .unwrap();
+        let al = AnnusLiturgicus::new(2024, regio("ecclesia_universalis")).unwrap();
+        let order = |month: u32, day: u32| -> Vec<String> {
+            let date = NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+            let mut rng = StdRng::seed_from_u64(0);
+            get_order(&mut rng, &configs[0], &al, date)
+                .unwrap()
+                .iter()
+                .map(|p| p.get_file())
+                .collect()
+        };
+        assert_eq!(order(3, 1), ["a", "c"]);
+        assert!(order(4, 10).is_empty());
+        assert_eq!(order(5, 19), ["d"]);
+        assert_eq!(order(7, 5), ["b", "c"]);
+        assert!(
+            parse_prayer_sets("title: a\norder:\n  - a:\n      when: {season: summer}\n").is_err()
+        );
+        assert!(parse_prayer_sets("title: a\norder:\n  - a:\n      when: {month: 13}\n").is_err());
+    }
+
+    #[test]
+    fn nested_groups() {
+        let configs = parse_prayer_sets(
+            "title: a
+prayers:
+  psalmi:
+    - [antiphona_a, psalmus_4, gloria_patri]
+    - [antiphona_b, psalmus_90, gloria_patri]
+  officium:
+    - psalmi: {count: 1, random: true}
+    - sequence: [capitulum, responsorium]
+      count: 2
+order:
+  - officium
+",
+        )
+        .unwrap();
+        let al = AnnusLiturgicus::new(2024, regio("ecclesia_universalis")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 7, 5).unwrap();
+        let order: Vec<String> = get_order(&mut StdRng::seed_from_u64(0), &configs[0], &al, date)
+            .unwrap()
+            .iter()
+            .map(|p| p.get_file())
+            .collect();
+        assert_eq!(order.len(), 7);
+        assert!(order[0].starts_with("antiphona_"));
+        assert_eq!(order[2], "gloria_patri");
+        assert_eq!(
+            order[3..],
+            ["capitulum", "responsorium", "capitulum", "responsorium"]
+        );
+
+        let err = parse_prayer_sets("title: a\nprayers:\n  a: [b]\n  b: [[c, a]]\norder: [a]\n")
+            .unwrap_err();
+        match err {
+            ConfigError::Cycle { path, .. } => assert_eq!(path, ["a", "b", "a"]),
+            _ => panic!("expected cycle error"),
+        }
+    }
+
+    #[test]
+    fn cycle_mode() {
+        let configs = parse_prayer_sets(
+            "title: a
+prayers:
+  g: [a, b, c, d]
+  h: [w1, w2, w3, w4]
+order:
+  - g: {count: 1, mode: cycle}
+  - h: {count: 1, mode: cycle, period: liturgical_week}
+",
+        )
+        .unwrap();
+        let order = |year: i32, month: u32, day: u32| -> Vec<String> {
+            let al = AnnusLiturgicus::new(year, regio("ecclesia_universalis")).unwrap();
+            let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+            get_order(&mut StdRng::seed_from_u64(0), &configs[0], &al, date)
+                .unwrap()
+                .iter()
+                .map(|p| p.get_file())
+                .collect()
+        };
+        let g = ["a", "b", "c", "d"];
+        let today = g.iter().position(|p| *p == order(2024, 7, 5)[0]).unwrap();
+        assert_eq!(order(2024, 7, 6)[0], g[(today + 1) % 4]);
+        assert_eq!(order(2023, 12, 3)[1], "w1");
+        assert_eq!(order(2023, 12, 10)[1], "w2");
+        assert_eq!(order(2024, 1, 7)[1], "w2");
+        // without a count, one member is taken each period
+        let configs = parse_prayer_sets(
+            "title: a
+prayers:
+  g: [a, b, c, d]
+order:
+  - g: {mode: cycle}
+  - g: {mode: cycle, period: week}
+",
+        )
+        .unwrap();
+        let al = AnnusLiturgicus::new(2024, regio("ecclesia_universalis")).unwrap();
+        let order = |month: u32, day: u32| -> Vec<String> {
+            let date = NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+            get_order(&mut StdRng::seed_from_u64(0), &configs[0], &al, date)
+                .unwrap()
+                .iter()
+                .map(|p| p.get_file())
+                .collect()
+        };
+        // Friday, Saturday and the Monday of the next week
+        let (friday, saturday, monday) = (order(7, 5), order(7, 6), order(7, 8));
+        assert_eq!(friday.len(), 2);
+        assert_ne!(friday[0], saturday[0]);
+        assert_ne!(saturday[0], order(7, 7)[0]);
+        assert_eq!(friday[1], saturday[1]);
+        assert_ne!(friday[1], monday[1]);
+        for entry in [
+            "x: {random: true, mode: cycle}",
+            "sequence: [a, b]\n    random: true\n    mode: cycle",
+        ] {
+            let err = parse_prayer_sets(&format!(
+                "title: a\norder:\n  - signum_crucis\n  - {}\n",
+                entry
+            ))
+            .unwrap_err();
+            assert!(matches!(
+                err,
+                ConfigError::Parse { order_index: Some(1), ref message, .. }
+                    if message.contains("contradicts mode: cycle")
+            ));
+        }
+    }
+
+    #[test]
+    fn weighted_selection() {
+        let configs = parse_prayer_sets(
+            "title: a
+prayers:
+  g:
+    - a: {weight: 0}
+    - b
+    - c: {weight: 3}
+order:
+  - g: {count: 2, random: true, unique: true}
+",
+        )
+        .unwrap();
+        let al = AnnusLiturgicus::new(2024, regio("ecclesia_universalis")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 7, 5).unwrap();
+        for seed in 0..20 {
+            let mut order: Vec<String> =
+                get_order(&mut StdRng::seed_from_u64(seed), &configs[0], &al, date)
+                    .unwrap()
+                    .iter()
+                    .map(|p| p.get_file())
+                    .collect();
+            order.sort();
+            assert_eq!(order, ["b", "c"]);
+        }
+    }
+
+    #[test]
+    fn seeds() {
+        use crate::cli::Args;
+        use crate::prayer::PrayerSet;
+        let args = |a: &[&str]| Args::parse(a.iter().map(|s| s.to_string()));
+        assert_eq!(args(&["--seed", "42"]).unwrap().seed, Some(42));
+        assert_eq!(args(&["check", "--seed=7"]).unwrap().seed, Some(7));
+        assert!(args(&["--seed"]).is_err());
+        assert!(args(&["--seed", "x"]).is_err());
+
+        let configs = parse_prayer_sets(
+            "title: a\nprayers:\n  x: [a, b, c, d, e, f, g, h]\norder:\n  - x: {random: true}\n",
+        )
+        .unwrap();
+        let al = AnnusLiturgicus::new(2024, regio("ecclesia_universalis")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 7, 5).unwrap();
+        let a = PrayerSet::new(&configs[0], 42, &al, date).unwrap();
+        let mut b = PrayerSet::new(&configs[0], 43, &al, date).unwrap();
+        assert_ne!(a, b);
+        b.reshuffle(42, &al, date).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(b.get_seed(), 42);
+    }
+
+    #[test]
+    fn rebuild_keeps_position() {
+        use crate::prayer::PrayerSet;
+        let configs =
+            parse_prayer_sets("title: a\norder: [a, b, c, d]\n---\ntitle: a\norder: [a, e]\n")
+                .unwrap();
+        let al = AnnusLiturgicus::new(2024, regio("ecclesia_universalis")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 7, 5).unwrap();
+        let mut prayer_set = PrayerSet::new(&configs[0], 3, &al, date).unwrap();
+        prayer_set.advance();
+        let rebuilt = prayer_set.rebuild(&configs[0], &al, date).unwrap();
+        assert_eq!(rebuilt.to_prayer().get_file(), "b");
+        prayer_set.advance();
+        prayer_set.advance();
+        let shortened = prayer_set.rebuild(&configs[1], &al, date).unwrap();
+        assert_eq!(shortened.to_prayer().get_file(), "e");
+        assert_eq!(shortened.get_seed(), 3);
+        // more prayers than fit into a byte
+        let names: Vec<String> = (0..300).map(|i| format!("p{}", i)).collect();
+        let config = format!("title: a\norder: [{}]\n", names.join(", "));
+        let configs = parse_prayer_sets(&config).unwrap();
+        let mut prayer_set = PrayerSet::new(&configs[0], 3, &al, date).unwrap();
+        for _ in 0..300 {
+            prayer_set.advance();
+        }
+        assert_eq!(prayer_set.to_prayer().get_file(), "p299");
+        let rebuilt = prayer_set.rebuild(&configs[0], &al, date).unwrap();
+        assert_eq!(rebuilt.to_prayer().get_file(), "p299");
+    }
+
+    #[test]
+    fn config_includes() {
+        use crate::config_parse::{merge_pages, read_pages_in};
+        let layer = Memory::new([
+            ("a.yaml", "title: a\n---\ninclude: \"novenae/*.yaml\"\n"),
+            ("novenae/b.yaml", "title: b\n"),
+            ("novenae/c.yaml", "title: c\n"),
+            ("loop.yaml", "include: [loop.yaml]\n"),
+        ]);
+
+        let mut pages = read_pages_in(&layer, "a.yaml").unwrap();
+        merge_pages(
+            &mut pages,
+            parse_pages("title: b\norder: [x]\n---\ntitle: d\n").unwrap(),
+        );
+        let titles: Vec<&str> = pages.iter().flatten().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, ["a", "b", "c", "d"]);
+        assert_eq!(pages[1].as_ref().unwrap().order.len(), 1);
+        assert!(matches!(
+            read_pages_in(&layer, "loop.yaml"),
+            Err(ConfigError::IncludeCycle { .. })
+        ));
+    }
+
+    // the data in the sources is only looked up by debug builds
+    #[cfg(debug_assertions)]
+    #[test]
+    fn data_roots() {
+        use crate::config::PRAYER_DIR;
+        use crate::data::data_roots;
+        use std::path::Path;
+        let dir = std::env::temp_dir().join("rosarium_data_roots");
+        std::fs::create_dir_all(&dir).unwrap();
+        let roots = data_roots(Some(dir.clone()));
+        assert_eq!(roots.first(), Some(&dir));
+        assert_eq!(
+            roots.last().map(|r| r.as_path()),
+            Some(Path::new(PRAYER_DIR))
+        );
+        assert_eq!(data_roots(Some(PRAYER_DIR.into())).len(), roots.len() - 1);
+        assert!(data_roots(Some(dir.join("missing"))).len() < roots.len());
+        // every directory is watched, system ones included
+        use crate::data::Dir;
+        let layers = Layers::new(vec![
+            Box::new(Dir::new(&dir)),
+            Box::new(Memory::new([("latina/credo", "Credo")])),
+            Box::new(Dir::new(PRAYER_DIR)),
+        ]);
+        assert_eq!(
+            layers.roots(),
+            vec![dir.clone(), Path::new(PRAYER_DIR).to_path_buf()]
+        );
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn layered_data() {
+        use crate::config_parse::layered_pages;
+        let layers = Layers::new(vec![
+            Box::new(Memory::new([
+                ("latina/ave_maria", "Ave Maria"),
+                (
+                    ".config.yaml",
+                    "title: b\norder: [y]\n---\ninclude: \"extra/*.yaml\"\n",
+                ),
+                ("extra/c.yaml", "title: c\n"),
+            ])),
+            Box::new(Memory::new([
+                ("latina/ave_maria", "Ave"),
+                ("latina/credo", "Credo"),
+                (".config.yaml", "title: a\n---\ntitle: b\norder: [x]\n"),
+            ])),
+        ]);
+        assert_eq!(layers.read("latina/ave_maria").unwrap(), "Ave Maria");
+        assert_eq!(layers.read("latina/credo").unwrap(), "Credo");
+        assert_eq!(layers.read_all("latina/ave_maria"), ["Ave Maria", "Ave"]);
+        assert!(layers.read("latina/pater_noster").is_err());
+        assert_eq!(layers.find("latina/credo"), None);
+
+        let pages = layered_pages(&layers).unwrap();
+        let titles: Vec<&str> = pages.iter().flatten().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, ["a", "b", "c"]);
+        assert_eq!(
+            pages[1].as_ref().unwrap().order,
+            [OrderEntry::Prayer("y".to_string())]
+        );
+    }
+
+    #[test]
+    fn corpus_cache() {
+        use crate::corpus::Corpus;
+        let layers = Layers::new(vec![
+            Box::new(Memory::new([
+                ("latina/.tituli", "ave_maria: Ave Maria\n"),
+                ("latina/credo", "Credo"),
+            ])),
+            Box::new(Memory::new([(
+                "latina/.tituli",
+                "ave_maria: Salutatio angelica\ncredo : Symbolum\n",
+            )])),
+        ]);
+        let changed = Layers::new(vec![Box::new(Memory::new([("latina/credo", "Credo in")]))]);
+        let mut corpus = Corpus::default();
+        assert_eq!(
+            corpus.text(&layers, "latina/credo").as_deref(),
+            Some("Credo")
+        );
+        assert_eq!(
+            corpus.text(&changed, "latina/credo").as_deref(),
+            Some("Credo")
+        );
+        assert_eq!(corpus.text(&layers, "latina/gloria"), None);
+        let title = |corpus: &mut Corpus, key| corpus.title(&layers, "latina", key);
+        assert_eq!(
+            title(&mut corpus, "ave_maria"),
+            Some(Some("Ave Maria".into()))
+        );
+        assert_eq!(title(&mut corpus, "credo"), Some(Some("Symbolum".into())));
+        assert_eq!(title(&mut corpus, "gloria"), Some(None));
+        assert_eq!(corpus.title(&layers, "germana", "credo"), None);
+        corpus = Corpus::default();
+        assert_eq!(
+            corpus.text(&changed, "latina/credo").as_deref(),
+            Some("Credo in")
+        );
+    }
+
+    #[test]
+    fn language_discovery() {
+        use crate::corpus::Corpus;
+        use crate::language::fallback_chain;
+        let layers = Layers::new(vec![
+            Box::new(Memory::new([
+                (
+                    "polska/.lingua",
+                    "name: Polski\ncode: pl\nfallback: [slovenica, anglia]\n",
+                ),
+                ("latina/.lingua", "name: Latina\ncode: la\n"),
+            ])),
+            Box::new(Memory::new([
+                (
+                    "anglia/.lingua",
+                    "name: English\ncode: en\nfallback: [latina]\n",
+                ),
+                ("latina/.lingua", "name: Lingua Latina\ncode: la\n"),
+                ("novenae/a.yaml", "title: a\n"),
+                ("hispania/.lingua", "nomen: Español\n"),
+            ])),
+        ]);
+        let mut corpus = Corpus::default();
+        let languages = corpus.languages(&layers).to_vec();
+        let dirs: Vec<&str> = languages.iter().map(|(dir, _)| dir.as_str()).collect();
+        assert_eq!(dirs, ["anglia", "hispania", "latina", "polska"]);
+        assert_eq!(languages[2].1.name, "Latina");
+        assert_eq!(languages[2].1.script, "Latn");
+        assert_eq!(languages[1].1.name, "hispania");
+        assert_eq!(fallback_chain("polska", &languages), ["anglia", "latina"]);
+        assert_eq!(fallback_chain("anglia", &languages), ["latina"]);
+        assert!(fallback_chain("latina", &languages).is_empty());
+    }
+
+    #[test]
+    fn fallback_languages() {
+        use crate::corpus::Corpus;
+        use crate::language::{fallback_chain, Language};
+        let layers = Layers::new(vec![Box::new(Memory::new([
+            (
+                "latina/.lingua",
+                "name: Latina\ncode: la\nfallback: [anglia]\n",
+            ),
+            (
+                "anglia/.lingua",
+                "name: English\ncode: en\nfallback: [latina]\n",
+            ),
+            (
+                "germana/.lingua",
+                "name: Deutsch\ncode: de\nfallback: [latina]\n",
+            ),
+            ("hispania/.lingua", "name: Español\ncode: es\n"),
+            ("germana/ave_maria", "Gegrüßet seist du, Maria"),
+            ("latina/ave_maria", "Ave Maria"),
+            ("latina/angele_dei", "Angele Dei"),
+            ("hispania/salve_regina", "Dios te salve, Reina y Madre"),
+        ]))]);
+        let mut corpus = Corpus::default();
+        let germana = Language::new("germana");
+        let (_, source) = corpus.text_in(&layers, &germana, "ave_maria").unwrap();
+        assert_eq!(source, germana);
+        let (text, source) = corpus.text_in(&layers, &germana, "angele_dei").unwrap();
+        assert_eq!(source, Language::new("latina"));
+        assert!(text.starts_with("Angele Dei"));
+        assert!(corpus.text_in(&layers, &germana, "nulla_oratio").is_none());
+        // only the declared fallbacks, not every other language
+        assert!(corpus.text_in(&layers, &germana, "salve_regina").is_none());
+        assert_eq!(
+            fallback_chain("germana", corpus.languages(&layers)),
+            ["latina", "anglia"]
+        );
+        assert_eq!(
+            crate::prayer::audio_path(&germana, "ave_maria"),
+            "germana/cantus/ave_maria.wav"
+        );
+    }
+
+    #[test]
+    fn interlinear() {
+        use crate::tui_util::interleave;
+        let (lines, translated) = interleave(
+            "Pater noster,\nqui es in caelis,\n\nAmen.\n",
+            "Vater unser\nim Himmel,\n\nAmen.\nEin Zusatz",
+        );
+        assert_eq!(
+            lines,
+            "Pater noster,\nVater unser\nqui es in caelis,\nim Himmel,\n\nAmen.\nAmen.\n\nEin Zusatz"
+        );
+        assert_eq!(
+            translated,
+            [false, true, false, true, false, false, true, false, true]
+        );
+    }
+
+    #[test]
+    fn linked_windows() {
+        use crate::tui::{Window, WindowStack};
+        let layers = Layers::new(vec![Box::new(Memory::new([(
+            "latina/.lingua",
+            "name: Latina\ncode: la\n",
+        )]))]);
+        let window = || {
+            let regiones = vec![regio("ecclesia_universalis").clone()];
+            WindowStack::Node(Window::new_in(&layers, &[], regiones, 0, Some(1)).unwrap())
+        };
+        let mut first = window();
+        first.windows_mut()[0].is_active = true;
+        let mut ws = WindowStack::HSplit(
+            Box::new(WindowStack::VSplit(Box::new(first), Box::new(window()))),
+            Box::new(window()),
+        );
+        let active = |ws: &mut WindowStack| {
+            ws.windows_mut()
+                .into_iter()
+                .position(|w| w.is_active)
+                .unwrap()
+        };
+        ws.toggle_link();
+        ws.windows_mut()[0].rosary.advance();
+        ws.sync_linked();
+        ws.focus_next();
+        assert_eq!(active(&mut ws), 1);
+        ws.toggle_link();
+        let position = ws.windows_mut()[1].rosary;
+        assert_ne!(position, Rosary::new());
+        ws.windows_mut()[1].rosary.advance();
+        ws.sync_linked();
+        let rosaries: Vec<Rosary> = ws.windows_mut().iter().map(|w| w.rosary).collect();
+        assert_eq!(rosaries[0], rosaries[1]);
+        assert_ne!(rosaries[0], position);
+        assert_eq!(rosaries[2], Rosary::new());
+    }
+
+    #[test]
+    fn ui_translation() {
+        use crate::corpus::Corpus;
+        use crate::language::{get_ui_translation_in, Language, UI_KEYS};
+        let layers = linguae();
+        let mut corpus = Corpus::default();
+        let mut get_ui_translation =
+            |key: &str, lan: &Language| get_ui_translation_in(&mut corpus, &layers, key, lan);
+        let anglia = Language::new("anglia");
+        assert_eq!(get_ui_translation("claves/exire", &anglia), "Quit");
+        assert_eq!(
+            get_ui_translation("dominica", &Language::new("germana")),
+            "So"
+        );
+        assert_eq!(
+            get_ui_translation("calendarium", &Language::new("slavonica_antiqua")),
+            "Calendar"
+        );
+        assert_eq!(get_ui_translation("nulla_clavis", &anglia), "nulla_clavis");
+        assert_eq!(
+            get_ui_translation("causa/feria_sexta", &Language::new("germana")),
+            "Freitag"
+        );
+        assert_eq!(
+            get_ui_translation("regio/anglia_et_cambria", &anglia),
+            "England and Wales"
+        );
+        for key in UI_KEYS {
+            assert_ne!(get_ui_translation(key, &anglia), key);
+        }
+    }
+
+    #[test]
+    fn localized_progress() {
+        use crate::corpus::Corpus;
+        use crate::language::{fill_ordinals, Language};
+        let ordinal = |form: &str, n: u8| match (form, n) {
+            ("n_acc", 1) => Some(String::from("primum")),
+            ("n_gen", 2) => Some(String::from("secundi")),
+            _ => None,
+        };
+        assert_eq!(
+            fill_ordinals("ad {1:n_acc} nodum {2:n_gen} decennii", &[1, 2], ordinal),
+            "ad primum nodum secundi decennii"
+        );
+        assert_eq!(
+            fill_ordinals("{1:n_acc^} {3} {2}", &[1, 4], ordinal),
+            "Primum {3} 4"
+        );
+
+        let layers = linguae();
+        let mut corpus = Corpus::default();
+        let mut progress =
+            |rosary: &Rosary, lan: &Language| rosary.progress_in(&mut corpus, &layers, lan);
+        let latina = Language::new("latina");
+        let anglia = Language::new("anglia");
+        let mut rosary = Rosary::new();
+        assert_eq!(progress(&rosary, &latina), "Manus ad crucifixum.");
+        for _ in 0..10 {
+            rosary.advance();
+        }
+        assert_eq!(
+            progress(&rosary, &latina),
+            "Manus ad secundum nodum primi decennii."
+        );
+        assert_eq!(
+            progress(&rosary, &anglia),
+            "Hand on the second bead of the first decade."
+        );
+        for _ in 0..200 {
+            for lan in [&latina, &anglia, &Language::new("germana")] {
+                assert!(!progress(&rosary, lan).contains('{'));
+            }
+            rosary.advance();
+        }
+        assert_eq!(
+            Rosary::new().progress_in(&mut Corpus::default(), &Layers::new(vec![]), &latina),
+            "positio/crucifixum"
+        );
+    }
+
+    #[test]
+    fn church_slavonic_scripts() {
+        use crate::scriptura::{transcribe, Scriptura};
+        let text = "Гдⷭ҇и Іи҃се Хрⷭ҇тѐ, Сн҃е Бж҇їй,\nпоми́лѹй мѧ̀ грѣ́шнаго.";
+        assert_eq!(transcribe(text, Scriptura::Ecclesiastica), text);
+        assert_eq!(
+            transcribe(text, Scriptura::SineTitlis),
+            "Господи Іисусе Христе, Сыне Божїй,\nпомилѹй мѧ грѣшнаго."
+        );
+        assert_eq!(
+            transcribe(text, Scriptura::Civilis),
+            "Господи Иисусе Христе, Сыне Божий,\nпомилуй мя грешнаго."
+        );
+        assert_eq!(
+            transcribe(text, Scriptura::Latina),
+            "Gospodi Iisuse Khriste, Syne Bozhij,\npomiluj mya greshnago."
+        );
+        assert_eq!(transcribe("Богъ", Scriptura::Civilis), "Бог");
+        assert_eq!(
+            transcribe("Сн҃е", Scriptura::Glagolitica),
+            "\u{2C14}\u{2C4F}\u{2C3B}\u{2C40}\u{2C35}"
+        );
+    }
+
+    #[test]
+    fn latin_vowel_marks() {
+        use crate::orthographia::{scribe, Orthographia};
+        let text = "Ave Marīa, grātia plena, Dōminus tecum:\nbenedīcta tu in muliēribus";
+        assert_eq!(scribe(text, Orthographia::Longae, false), text);
+        assert_eq!(
+            scribe(text, Orthographia::Acuta, false),
+            "Ave María, grátia plena, Dóminus tecum:\nbenedícta tu in muliéribus"
+        );
+        assert_eq!(
+            scribe(text, Orthographia::Plana, false),
+            "Ave Maria, gratia plena, Dominus tecum:\nbenedicta tu in mulieribus"
+        );
+        assert_eq!(
+            scribe("Ave grātia", Orthographia::Longae, true),
+            "\u{2}A\u{3}ve gr\u{2}ā\u{3}tia"
+        );
+        assert_eq!(
+            scribe(
+                "peccatoribus, benedictus, Iesus, quaesumus",
+                Orthographia::Acuta,
+                false
+            ),
+            "peccatóribus, benedíctus, Iesus, quáesumus"
+        );
+    }
+
+    #[test]
+    fn translation_coverage() {
+        use crate::coverage::Coverage;
+        let layers = Layers::new(vec![Box::new(Memory::new([
+            ("latina/.lingua", "name: Latina\ncode: la\n"),
+            ("anglia/.lingua", "name: English\ncode: en\n"),
+            (
+                "latina/.tituli",
+                "pater_noster: Pater noster\nangele_dei: Angele Dei\nmysteria/mysteria_gaudiosa: Mysteria gaudiosa\n",
+            ),
+            ("anglia/.tituli", "pater_noster: Our Father\n"),
+            ("latina/pater_noster", "Pater noster"),
+            ("anglia/pater_noster", "Our Father"),
+            ("latina/angele_dei", "Angele Dei"),
+            ("latina/mysteria/initium", "In nomine Patris"),
+            ("latina/mysteria/mysteria_gaudiosa", "Annuntiatio"),
+            ("anglia/mysteria/mysteria_gaudiosa", "The Annunciation"),
+        ]))]);
+        let pages = parse_pages("title: a\norder: [salve_regina]\n").unwrap();
+        let coverage = Coverage::new_in(&layers, &pages);
+        let gaps = coverage.gaps();
+        assert!(gaps.contains(&String::from("anglia: mysteria/initium missing")));
+        assert!(gaps.contains(&String::from("anglia: angele_dei missing")));
+        assert!(gaps.contains(&String::from("latina: salve_regina missing")));
+        assert!(gaps.contains(&String::from(
+            "anglia: no title for \"mysteria/mysteria_gaudiosa\""
+        )));
+        assert!(!gaps
+            .iter()
+            .any(|gap| gap.starts_with("latina: mysteria/mysteria_")));
+        let initium = coverage
+            .files
+            .iter()
+            .find(|f| f.file == "mysteria/initium")
+            .unwrap();
+        assert!(initium.presence.iter().all(|p| p.title.is_none()));
+        assert!(coverage.files.iter().any(|f| f.file == "pater_noster"));
+        assert!(coverage.to_string().lines().count() > coverage.files.len());
+    }
+
+    #[test]
+    fn right_to_left() {
+        use crate::directio::visual;
+        use crate::tui_util::line_width;
+        assert_eq!(line_width("Бж҃е"), 3);
+        assert_eq!(line_width("Übersetzung"), 11);
+        assert_eq!(line_width("\u{2}A\u{3}ve"), 3);
+        assert_eq!(visual("שלום (עולם) 12 abc"), "12 abc (םלוע) םולש");
+        assert_eq!(visual("שָׁלוֹם"), "םוֹלשָׁ");
+        assert_eq!(visual("אב\nא"), "בא\n א");
+        assert_eq!(visual("א ⸨ב⸩"), "⸨ב⸩ א");
+    }
+
+    #[test]
+    fn prayer_markup() {
+        use crate::tui_util::{line_width, styled};
+        use ratatui::style::{Color, Modifier};
+        let text = styled("℣. Ora pro nobis ⸨genuflectitur⸩\n   Sancta Dei Genitrix.\n℟: Ut [[digni]] efficiamur\n> Oremus.\nAmen.");
+        let content = |i: usize| -> Vec<String> {
+            text.lines[i]
+                .spans
+                .iter()
+                .map(|span| span.content.to_string())
+                .collect()
+        };
+        let bold = |i: usize| text.lines[i].style.add_modifier.contains(Modifier::BOLD);
+        assert_eq!(content(0), ["℣.", " Ora pro nobis ", "genuflectitur"]);
+        assert_eq!(text.lines[0].spans[0].style.fg, Some(Color::Red));
+        assert_eq!(text.lines[0].spans[2].style.fg, Some(Color::Red));
+        assert!(bold(0) && bold(1));
+        assert_eq!(content(2), ["℟:", " Ut ", "digni", " efficiamur"]);
+        assert_eq!(text.lines[2].spans[2].style.fg, Some(Color::DarkGray));
+        assert!(!bold(2));
+        assert_eq!(content(3), ["Oremus."]);
+        assert_eq!(line_width("> Oremus ⸨genuflectitur⸩"), 20);
+        // braces are placeholders of interface phrases, not rubrics
+        let phrase = styled("{1:n_acc^} mysterium");
+        assert_eq!(phrase.lines[0].spans[0].content, "{1:n_acc^} mysterium");
+        assert_eq!(line_width("{1:n_acc^}"), 10);
+        assert!(bold(3) && !bold(4));
+    }
 }
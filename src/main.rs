@@ -11,21 +11,62 @@ use crossterm::{
 };
 
 use ratatui::{backend::CrosstermBackend, Terminal};
+use rosarium::check::{check, exit_code, Severity};
+use rosarium::cli::{Args, USAGE};
+use rosarium::coverage::Coverage;
+use rosarium::data;
 use rosarium::render::redraw;
 use rosarium::tui::{key_listen, Event, Frame, MenuItem};
+use rosarium::watch::watch_preces;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     use std::time::Duration;
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+    data::init(args.data_dir)?;
+    if args.check {
+        let findings = check();
+        for finding in &findings {
+            println!("{}", finding);
+        }
+        let errors = findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .count();
+        println!(
+            "{} error(s), {} warning(s)",
+            errors,
+            findings.len() - errors
+        );
+        std::process::exit(exit_code(&findings, args.strict));
+    }
+    if args.coverage {
+        let coverage = Coverage::new()?;
+        println!("{}\n", coverage);
+        let gaps = coverage.gaps();
+        for gap in &gaps {
+            println!("{}", gap);
+        }
+        println!("{} gap(s)", gaps.len());
+        return Ok(());
+    }
+
     stdout()
         .execute(event::EnableMouseCapture)
         .expect("No mouse capture support");
     enable_raw_mode().expect("can not run in raw mode");
 
-    let mut frame = Frame::new()?;
+    let mut frame = Frame::new(args.seed)?;
 
     // Event loop
     let (tx, rx) = mpsc::channel();
     let tick_rate = Duration::from_millis(200);
+    watch_preces(tx.clone())?;
 
     // Create terminal
     let stdout = io::stdout();
@@ -45,7 +86,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 // Handle key events
                 if event::poll(timeout).expect("poll works") {
                     if let CEvent::Key(key) = event::read().expect("can't read events") {
-                        tx.send(Event::Input(key)).expect("can't send events");
                         tx.send(Event::Input(key)).expect("can't send events");
                         // println!("{:?}", Event::Input(key));
                     } else if let CEvent::Resize(x, y) = event::read().expect("can't read events") {
@@ -0,0 +1,81 @@
+//! Markup of prayer files, for praying them with a leader and a congregation
+
+/// Begins a versicle, said by the leader
+pub const VERSICULUS: char = '℣';
+/// Begins a response, said by the congregation
+pub const RESPONSORIUM: char = '℟';
+/// Begins another line said by the leader
+pub const PRAECENTOR: &str = "> ";
+/// Put around instructions such as "kneel", which are not said. Double parentheses,
+/// since braces are the placeholders of interface phrases
+pub const RUBRICA_START: char = '⸨';
+pub const RUBRICA_END: char = '⸩';
+/// Put around parts that may be left out
+pub const OPTIO_START: &str = "[[";
+pub const OPTIO_END: &str = "]]";
+
+/// Who says a line
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Pars {
+    Versiculus,
+    Responsorium,
+    Praecentor,
+}
+
+/// A line split by its markup
+#[derive(Debug, Eq, PartialEq)]
+pub struct Linea<'a> {
+    pub indent: &'a str,
+    pub pars: Option<Pars>,
+    /// "℣.", "℟:" or the like, empty for other lines
+    pub signum: &'a str,
+    pub text: &'a str,
+}
+
+/// A line continuing the part of a line before, and the indentation of that line
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Continuatio(Option<(Pars, usize)>);
+
+/// Split the markup off `line`. A line indented deeper than the versicle, response or
+/// leader line before it belongs to it
+pub fn linea<'a>(line: &'a str, continuatio: &mut Continuatio) -> Linea<'a> {
+    let text = line.trim_start();
+    let indent = &line[..line.len() - text.len()];
+    let (pars, marker) = if text.starts_with(VERSICULUS) {
+        (Some(Pars::Versiculus), signum(text))
+    } else if text.starts_with(RESPONSORIUM) {
+        (Some(Pars::Responsorium), signum(text))
+    } else if text.starts_with(PRAECENTOR) {
+        (Some(Pars::Praecentor), PRAECENTOR)
+    } else {
+        (None, "")
+    };
+    match pars {
+        Some(pars) => *continuatio = Continuatio(Some((pars, indent.len()))),
+        None => {
+            if !matches!(continuatio.0, Some((_, i)) if indent.len() > i && !text.is_empty()) {
+                *continuatio = Continuatio::default();
+            }
+        }
+    }
+    Linea {
+        indent,
+        pars: continuatio.0.map(|(pars, _)| pars),
+        signum: if pars == Some(Pars::Praecentor) {
+            ""
+        } else {
+            marker
+        },
+        text: &text[marker.len()..],
+    }
+}
+
+/// The sign of a versicle or response with the punctuation after it
+fn signum(text: &str) -> &str {
+    let end = text
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !matches!(c, '.' | ':'))
+        .map_or(text.len(), |(i, _)| i);
+    &text[..end]
+}
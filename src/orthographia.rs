@@ -0,0 +1,238 @@
+//! Pronunciation aids for Latin texts, which mark stressed or long vowels with macrons
+
+/// BCP 47 code of the languages the aids apply to
+pub const LATIN: &str = "la";
+
+/// Put around the stressed vowel of a word to highlight it
+pub const STRESS_START: char = '\u{2}';
+pub const STRESS_END: char = '\u{3}';
+
+/// How vowels of Latin texts are marked
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Orthographia {
+    /// Macrons as in the prayer files
+    Longae,
+    /// Acute accents on the stressed vowel of words of three or more syllables, as in
+    /// liturgical books
+    Acuta,
+    Plana,
+}
+
+impl Orthographia {
+    pub fn cycle(self) -> Orthographia {
+        match self {
+            Orthographia::Longae => Orthographia::Acuta,
+            Orthographia::Acuta => Orthographia::Plana,
+            Orthographia::Plana => Orthographia::Longae,
+        }
+    }
+}
+
+/// A vowel or diphthong of a word, by indices into its characters
+struct Syllable {
+    start: usize,
+    end: usize,
+    /// Marked long or stressed
+    marked: bool,
+    diphthong: bool,
+}
+
+/// Write the Latin `text` in `orthographia`, with the stressed vowels between
+/// `STRESS_START` and `STRESS_END` if `stress`
+pub fn scribe(text: &str, orthographia: Orthographia, stress: bool) -> String {
+    if orthographia == Orthographia::Longae && !stress {
+        return text.to_string();
+    }
+    let mut written = String::new();
+    let mut word = vec![];
+    for c in text.chars() {
+        if c.is_alphabetic() || c == '\u{0304}' || c == '\u{0301}' {
+            word.push(c);
+        } else {
+            written += &scribe_word(&word, orthographia, stress);
+            word.clear();
+            written.push(c);
+        }
+    }
+    written + &scribe_word(&word, orthographia, stress)
+}
+
+fn scribe_word(word: &[char], orthographia: Orthographia, stress: bool) -> String {
+    // combining marks are folded into the letter before
+    let mut letters: Vec<char> = vec![];
+    let mut marked: Vec<bool> = vec![];
+    let mut original: Vec<String> = vec![];
+    for &c in word {
+        match (c, original.last_mut()) {
+            ('\u{0304}' | '\u{0301}', Some(last)) => {
+                last.push(c);
+                if let Some(mark) = marked.last_mut() {
+                    *mark = true;
+                }
+            }
+            _ => {
+                let (letter, mark) = plain(c);
+                letters.push(letter);
+                marked.push(mark);
+                original.push(c.to_string());
+            }
+        }
+    }
+    let syllables = syllables(&letters, &marked);
+    let stressed = stressed(&letters, &syllables);
+    let accent = orthographia == Orthographia::Acuta && syllables.len() >= 3;
+
+    let mut written = String::new();
+    for (i, letter) in letters.iter().enumerate() {
+        let start = stressed.is_some_and(|s| s.start == i);
+        if stress && start {
+            written.push(STRESS_START);
+        }
+        match orthographia {
+            Orthographia::Longae => written += &original[i],
+            Orthographia::Acuta if accent && start => written += &acute(*letter),
+            _ => written.push(*letter),
+        }
+        if stress && stressed.is_some_and(|s| s.end == i) {
+            written.push(STRESS_END);
+        }
+    }
+    written
+}
+
+/// The letter without macron or acute, and whether it had one
+fn plain(c: char) -> (char, bool) {
+    const MARKED: [(char, char); 28] = [
+        ('ā', 'a'),
+        ('ē', 'e'),
+        ('ī', 'i'),
+        ('ō', 'o'),
+        ('ū', 'u'),
+        ('ȳ', 'y'),
+        ('ǣ', 'æ'),
+        ('á', 'a'),
+        ('é', 'e'),
+        ('í', 'i'),
+        ('ó', 'o'),
+        ('ú', 'u'),
+        ('ý', 'y'),
+        ('ǽ', 'æ'),
+        ('Ā', 'A'),
+        ('Ē', 'E'),
+        ('Ī', 'I'),
+        ('Ō', 'O'),
+        ('Ū', 'U'),
+        ('Ȳ', 'Y'),
+        ('Ǣ', 'Æ'),
+        ('Á', 'A'),
+        ('É', 'E'),
+        ('Í', 'I'),
+        ('Ó', 'O'),
+        ('Ú', 'U'),
+        ('Ý', 'Y'),
+        ('Ǽ', 'Æ'),
+    ];
+    MARKED
+        .iter()
+        .find(|(marked, _)| *marked == c)
+        .map_or((c, false), |(_, plain)| (*plain, true))
+}
+
+fn acute(c: char) -> String {
+    match c {
+        'a' => "á",
+        'e' => "é",
+        'i' => "í",
+        'o' => "ó",
+        'u' => "ú",
+        'y' => "ý",
+        'æ' => "ǽ",
+        'A' => "Á",
+        'E' => "É",
+        'I' => "Í",
+        'O' => "Ó",
+        'U' => "Ú",
+        'Y' => "Ý",
+        'Æ' => "Ǽ",
+        c => return format!("{}\u{0301}", c),
+    }
+    .to_string()
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(
+        c.to_lowercase().next().unwrap_or(c),
+        'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'æ' | 'œ'
+    )
+}
+
+/// The vowels and diphthongs of a word. I and u count as consonants where they stand
+/// for j and v, as in "Iesus", "eius", "qui" and "sanguis"
+fn syllables(letters: &[char], marked: &[bool]) -> Vec<Syllable> {
+    let lower: Vec<char> = letters
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let vowel_at = |i: usize| i < lower.len() && is_vowel(lower[i]);
+    let mut syllables: Vec<Syllable> = vec![];
+    let mut i = 0;
+    while i < lower.len() {
+        let c = lower[i];
+        let consonantal = match c {
+            'i' => (i == 0 || vowel_at(i - 1)) && vowel_at(i + 1) && !marked[i],
+            'u' => {
+                i > 0
+                    && (lower[i - 1] == 'q' || (i > 1 && lower[i - 2..i] == ['n', 'g']))
+                    && vowel_at(i + 1)
+            }
+            _ => false,
+        };
+        if !is_vowel(c) || consonantal {
+            i += 1;
+            continue;
+        }
+        let next = lower.get(i + 1).copied();
+        let diphthong = matches!((c, next), ('a', Some('e' | 'u')) | ('o', Some('e')));
+        let end = if diphthong { i + 1 } else { i };
+        syllables.push(Syllable {
+            start: i,
+            end,
+            marked: marked[i..=end].iter().any(|m| *m),
+            diphthong: diphthong || c == 'æ' || c == 'œ',
+        });
+        i = end + 1;
+    }
+    syllables
+}
+
+/// The stressed syllable: a marked one of the last three, or else the penult if it is
+/// long and the antepenult if not
+fn stressed<'a>(letters: &[char], syllables: &'a [Syllable]) -> Option<&'a Syllable> {
+    let n = syllables.len();
+    if n < 2 {
+        return None;
+    }
+    if n == 2 {
+        return syllables.first();
+    }
+    let penult = &syllables[n - 2];
+    let antepenult = &syllables[n - 3];
+    if penult.marked {
+        return Some(penult);
+    }
+    if antepenult.marked {
+        return Some(antepenult);
+    }
+    let consonants: Vec<char> = letters[penult.end + 1..syllables[n - 1].start]
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .filter(|c| *c != 'h')
+        .collect();
+    let muta_cum_liquida = consonants.len() == 2
+        && matches!(consonants[0], 'b' | 'c' | 'd' | 'g' | 'p' | 't' | 'f')
+        && matches!(consonants[1], 'l' | 'r');
+    let long = penult.diphthong
+        || consonants.iter().any(|c| matches!(c, 'x' | 'z'))
+        || (consonants.len() >= 2 && !muta_cum_liquida && consonants[..2] != ['q', 'u']);
+    Some(if long { penult } else { antepenult })
+}
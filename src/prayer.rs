@@ -1,93 +1,78 @@
-use crate::config_parse::get_order;
-use crate::tui::E;
-use crate::{
-    config::PRAYER_DIR,
-    language::{get_title_translation, Language},
-};
+use crate::calender::AnnusLiturgicus;
+use crate::config::AUDIO_DIR;
+use crate::config_parse::{get_order, PrayerSetConfig};
+use crate::corpus;
+use crate::language::{get_title_translation, Language};
+use crate::tui::{E, R};
+use chrono::NaiveDate;
 use rand::rngs::StdRng;
-use std::fs;
-use std::path::Path;
+use rand::SeedableRng;
+use std::fmt;
 use std::str::FromStr;
-use yaml_rust::Yaml;
+
+/// Path of the recording of the prayer file `file` in `lan`
+pub fn audio_path(lan: &Language, file: &str) -> String {
+    lan.to_string() + "/" + AUDIO_DIR + "/" + file + ".wav"
+}
 
 pub trait Prayer {
     fn get_file(&self) -> String;
 
     fn load_audio(&self, lan: &Language) -> Option<String> {
-        let audio_file =
-            PRAYER_DIR.to_owned() + "/" + &lan.to_string() + "/cantus/" + &self.get_file() + ".wav";
-        if Path::new(&audio_file).exists() {
-            Some(audio_file)
-        } else {
-            self.load_fallback_prayer_audio(lan)
+        match corpus::file(&audio_path(lan, &self.get_file())) {
+            Some(audio_file) => Some(audio_file.display().to_string()),
+            None => self.load_fallback_prayer_audio(lan),
         }
     }
 
-    fn load_fallback_prayer_audio(&self, _lan: &Language) -> Option<String> {
-        for lan in Language::VALUES.iter() {
-            let audio_file = PRAYER_DIR.to_owned()
-                + "/"
-                + &lan.to_string()
-                + "/cantus/"
-                + &self.get_file()
-                + ".wav";
-            if Path::new(&audio_file).exists() {
-                return Some(audio_file);
+    fn load_fallback_prayer_audio(&self, lan: &Language) -> Option<String> {
+        for lan in lan.fallbacks() {
+            if let Some(audio_file) = corpus::file(&audio_path(&lan, &self.get_file())) {
+                return Some(audio_file.display().to_string());
             }
         }
         None
     }
 
+    /// Languages having a recording of the prayer
     fn get_available_languages(&self) -> Vec<Language> {
-        let mut languages = vec![];
-        for lan in Language::VALUES.iter() {
-            let audio_file =
-                PRAYER_DIR.to_owned() + "/" + &lan.to_string() + "/" + &self.get_file() + ".wav";
-            if Path::new(&audio_file).exists() {
-                languages.push(lan.to_owned());
-            }
-        }
-        return languages;
+        let file = self.get_file();
+        Language::all()
+            .into_iter()
+            .filter(|lan| corpus::file(&audio_path(lan, &file)).is_some())
+            .collect()
     }
 
     fn get_prayer_title(&self, lan: &Language) -> String {
         get_title_translation(&self.get_file(), lan)
     }
 
-    fn get_prayer_text_title(&self, lan: &Language) -> (String, String) {
-        let file = PRAYER_DIR.to_owned() + "/" + &lan.to_string() + "/" + &self.get_file();
-        let text = fs::read_to_string(&file);
-        if text.is_ok() {
-            (text.unwrap(), self.get_prayer_title(lan))
-        } else {
-            let (text, lan) = self.get_fallback_prayer_text();
-            (text, self.get_prayer_title(lan))
-        }
+    /// Text and title of the prayer, with the language the text was taken from
+    fn get_prayer_text_title(&self, lan: &Language) -> (String, String, Language) {
+        let (text, source) = self.get_fallback_prayer_text(lan);
+        (text, self.get_prayer_title(lan), source)
     }
 
     fn get_prayer_text_for_language(&self, lang: &Language) -> String {
-        let file = PRAYER_DIR.to_owned() + "/" + &lang.to_string() + "/" + &self.get_file();
-        fs::read_to_string(&file).unwrap_or(format!("{} not found", lang.to_string()))
+        let file = lang.to_string() + "/" + &self.get_file();
+        corpus::text(&file).unwrap_or(format!("{} not found", lang))
     }
 
-    fn get_fallback_prayer_text(&self) -> (String, &Language) {
-        for lan in Language::VALUES.iter() {
-            let file = PRAYER_DIR.to_owned() + "/" + &lan.to_string() + "/" + &self.get_file();
-            let prayer_text = fs::read_to_string(&file);
-            if prayer_text.is_ok() {
-                return (prayer_text.unwrap(), &lan);
-            }
-        }
-        return (
-            format!("Unable find prayer at {}", &self.get_file()),
-            &Language::ANGLIA,
-        );
+    /// Text from `lan` or else from the first of its fallback languages having the prayer
+    fn get_fallback_prayer_text(&self, lan: &Language) -> (String, Language) {
+        corpus::text_in(lan, &self.get_file()).unwrap_or_else(|| {
+            (
+                format!("Unable find prayer at {}", self.get_file()),
+                lan.clone(),
+            )
+        })
     }
 
-    fn title_text_audio(&self, lan: &Language) -> (String, String, Option<String>) {
+    /// Title, text, audio and the language the text was taken from
+    fn title_text_audio(&self, lan: &Language) -> (String, String, Option<String>, Language) {
         let audio = self.load_audio(lan);
-        let (text, title) = self.get_prayer_text_title(lan);
-        (title, text, audio)
+        let (text, title, source) = self.get_prayer_text_title(lan);
+        (title, text, audio, source)
     }
 }
 
@@ -103,9 +88,9 @@ impl std::cmp::PartialEq for dyn Prayer {
     }
 }
 
-impl ToString for Box<dyn Prayer> {
-    fn to_string(&self) -> String {
-        String::from(self.get_file())
+impl fmt::Display for Box<dyn Prayer> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_file())
     }
 }
 
@@ -137,7 +122,7 @@ pub struct _Prayer {
 
 impl Prayer for _Prayer {
     fn get_file(&self) -> String {
-        return String::from(&self.file);
+        String::from(&self.file)
     }
 }
 
@@ -151,34 +136,73 @@ impl _Prayer {
 pub struct PrayerSet {
     title: String,
     /// Number of current prayer
-    curr_prayer: u8,
+    curr_prayer: usize,
     prayers: Vec<Box<dyn Prayer>>,
+    config: PrayerSetConfig,
+    /// Seed the random selection of prayers was made with
+    seed: u64,
 }
 
 impl PrayerSet {
-    pub fn new(title: String, y: Yaml, rng: &mut StdRng) -> Result<PrayerSet, E> {
+    /// Build the prayer set as it is prayed on `date`
+    pub fn new(
+        config: &PrayerSetConfig,
+        seed: u64,
+        al: &AnnusLiturgicus,
+        date: NaiveDate,
+    ) -> Result<PrayerSet, E> {
         Ok(PrayerSet {
-            title,
+            title: config.title.clone(),
             curr_prayer: 0,
-            prayers: get_order(rng, &y)?,
+            prayers: get_order(&mut StdRng::seed_from_u64(seed), config, al, date)?,
+            config: config.clone(),
+            seed,
         })
     }
 
+    /// Select the prayers anew with another seed and start from the beginning
+    pub fn reshuffle(&mut self, seed: u64, al: &AnnusLiturgicus, date: NaiveDate) -> R {
+        self.prayers = get_order(&mut StdRng::seed_from_u64(seed), &self.config, al, date)?;
+        self.seed = seed;
+        self.curr_prayer = 0;
+        Ok(())
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Title as written in the configuration, before translation
+    pub fn get_key(&self) -> &str {
+        &self.title
+    }
+
+    /// Build the prayer set from a changed configuration with the same seed and position
+    pub fn rebuild(
+        &self,
+        config: &PrayerSetConfig,
+        al: &AnnusLiturgicus,
+        date: NaiveDate,
+    ) -> Result<PrayerSet, E> {
+        let mut prayer_set = PrayerSet::new(config, self.seed, al, date)?;
+        let last = prayer_set.prayers.len().saturating_sub(1);
+        prayer_set.curr_prayer = self.curr_prayer.min(last);
+        Ok(prayer_set)
+    }
+
     pub fn get_title(&self, lan: &Language) -> String {
         get_title_translation(&self.title, lan)
     }
 
     pub fn to_prayer(&self) -> Box<dyn Prayer> {
-        let o = self.prayers.get(self.curr_prayer as usize);
-        if o.is_some() {
-            o.unwrap().clone()
-        } else {
-            Box::default()
+        match self.prayers.get(self.curr_prayer) {
+            Some(prayer) => prayer.clone(),
+            None => Box::default(),
         }
     }
 
     pub fn advance(&mut self) {
-        if self.prayers.len() > 0 && (self.curr_prayer as usize) < self.prayers.len() - 1 {
+        if !self.prayers.is_empty() && self.curr_prayer < self.prayers.len() - 1 {
             self.curr_prayer += 1
         }
     }
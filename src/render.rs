@@ -1,10 +1,13 @@
-use crate::calender::AnnusLiturgicus;
+use crate::calender::{AnnusLiturgicus, Poenitentia, REGIO_NAMESPACE};
 use crate::events::get_keybindings;
-use crate::language::get_title_translation;
+use crate::language::{get_title_translation, get_ui_translation, Language};
 
+use crate::orthographia::{STRESS_END, STRESS_START};
 use crate::rosary::get_daily_mystery;
-use crate::tui::{Frame, MenuItem, Popup, Window, WindowStack};
-use crate::tui_util::{centered_rect, cursive_p, hcenter};
+use crate::tui::{Bilingual, Frame, MenuItem, Popup, Window, WindowStack};
+use crate::tui_util::{
+    centered_rect, cursive_p, hcenter, interleave, interlinear, interlinear_p, styled,
+};
 use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
@@ -18,29 +21,95 @@ use std::error::Error;
 use std::io::Stdout;
 use unicode_width::UnicodeWidthStr;
 
+/// Name of today in the list of feasts, shown translated
+const TODAY: &str = "Today";
+
+/// Interface keys of the days of the week from Sunday
+const WEEKDAYS: [&str; 7] = [
+    "dominica",
+    "feria_secunda",
+    "feria_tertia",
+    "feria_quarta",
+    "feria_quinta",
+    "feria_sexta",
+    "sabbatum",
+];
+
 pub fn render_prayer_set<'a>(window: &mut Window) -> Result<Paragraph<'a>, Box<dyn Error>> {
     let language = window.get_language().clone();
     let prayer_set = window.get_curr_prayer_set()?;
     let prayer = prayer_set.to_prayer();
-    let (title, text, audio) = prayer.title_text_audio(&language);
-    let prayer_render = cursive_p(text, prayer_set.get_title(&language), title, window);
+    let (title, text, audio, source) = prayer.title_text_audio(&language);
+    let border_title = format!(
+        "{} ({} {}){}",
+        prayer_set.get_title(&language),
+        get_ui_translation("semen", &language),
+        prayer_set.get_seed(),
+        fallback_mark(&language, &source)
+    );
+    let border_title = window
+        .transcribe(&language, border_title)
+        .replace([STRESS_START, STRESS_END], "");
+    let title = window.transcribe(&language, title);
+    let text = window.transcribe(&source, text);
+    let prayer_render = match window.get_bilingual() {
+        Bilingual::Interlinear => {
+            let translation = prayer.get_prayer_text_for_language(window.get_translation());
+            let translation = window.transcribe(window.get_translation(), translation);
+            interlinear_p(text, translation, border_title, title, window)
+        }
+        _ => cursive_p(text, border_title, title, window),
+    };
     window.audio = audio;
     Ok(prayer_render)
 }
 
+/// The current prayer of the prayer set in the language of the translation
+pub fn render_prayer_set_translation<'a>(
+    window: &mut Window,
+) -> Result<Paragraph<'a>, Box<dyn Error>> {
+    let language = window.get_translation().clone();
+    let prayer = window.get_curr_prayer_set()?.to_prayer();
+    let (text, source) = prayer.get_fallback_prayer_text(&language);
+    let text = window.transcribe(&source, text);
+    let title = window.transcribe(&language, prayer.get_prayer_title(&language));
+    let border_title = language.name() + &fallback_mark(&language, &source);
+    Ok(cursive_p(text, border_title, title, window))
+}
+
+/// Name the language a text was taken from instead of the chosen one
+fn fallback_mark(language: &Language, source: &Language) -> String {
+    if language == source {
+        String::new()
+    } else {
+        format!(" [{}]", source.name())
+    }
+}
+
 pub fn render_prayer<'a>(window: &mut Window) -> Result<Paragraph<'a>, Box<dyn Error>> {
     let rosary_prayer = window.rosary.to_prayer();
-    let mut prayer_words = rosary_prayer.get_prayer_text(window)?;
-    let mut prayer_title = rosary_prayer.get_prayer_title(window);
+    let (prayer_words, source) = rosary_prayer.get_prayer_text(window, window.get_language())?;
+    let mut prayer_words = window.transcribe(&source, prayer_words);
+    let prayer_title = rosary_prayer.get_prayer_title(window.get_language());
+    let mut prayer_title = window.transcribe(window.get_language(), prayer_title);
+    let mut translated = vec![];
+    if window.get_bilingual() == Bilingual::Interlinear {
+        let translation = rosary_prayer.get_prayer_text_for(window, window.get_translation())?;
+        let translation = window.transcribe(window.get_translation(), translation);
+        (prayer_words, translated) = interleave(&prayer_words, &translation);
+    }
     if rosary_prayer.is_mystery() {
         prayer_title = hcenter(&prayer_title, window);
         prayer_words = hcenter(&prayer_words, window);
     }
-    let prayer_text = Text::from(prayer_words);
+    let prayer_text = if translated.is_empty() {
+        styled(&prayer_words)
+    } else {
+        interlinear(prayer_words, &translated)
+    };
     let top_offset = window.get_top_offset(prayer_text.height() + 3);
     let cpt = String::from("\n") + &prayer_title + "\n" + &"\n".repeat(top_offset);
-    let prayer_width = cpt.width();
-    let mut centered_prayer_text: Text = Text::raw(cpt);
+    let mut centered_prayer_text: Text = styled(&cpt);
 
     if rosary_prayer.is_mystery() {
         centered_prayer_text = centered_prayer_text.patch_style(
@@ -70,13 +139,15 @@ pub fn render_prayer<'a>(window: &mut Window) -> Result<Paragraph<'a>, Box<dyn E
                         .fg(Color::White)
                         .remove_modifier(Modifier::ITALIC),
                 )
-                .title(get_title_translation("rosarium", window.get_language()))
-                .title(Line::from(window.get_language().to_string()).right_aligned())
+                .title(
+                    get_title_translation("rosarium", window.get_language())
+                        + &fallback_mark(window.get_language(), &source)
+                        + if window.is_linked() { " ⛓" } else { "" },
+                )
+                .title(Line::from(window.get_language().name()).right_aligned())
                 .border_type(BorderType::Rounded),
         );
     Ok(if rosary_prayer.is_mystery() {
-        let mut offset = window.get_offset();
-        offset.0 = window.get_vert_offset(prayer_width) as u16;
         rosarium
             .style(
                 Style::default()
@@ -95,18 +166,51 @@ pub fn render_prayer<'a>(window: &mut Window) -> Result<Paragraph<'a>, Box<dyn E
     })
 }
 
+/// The current prayer of the rosary in the language of the translation
+pub fn render_prayer_translation<'a>(window: &mut Window) -> Result<Paragraph<'a>, Box<dyn Error>> {
+    let language = window.get_translation().clone();
+    let rosary_prayer = window.rosary.to_prayer();
+    let (prayer_text, source) = rosary_prayer.get_prayer_text(window, &language)?;
+    let prayer_text = styled(&window.transcribe(&source, prayer_text));
+    let top_offset = window.get_top_offset(prayer_text.height() + 3);
+    let title = window.transcribe(&language, rosary_prayer.get_prayer_title(&language));
+    let mut text = styled(&(String::from("\n") + &title + "\n" + &"\n".repeat(top_offset)))
+        .patch_style(
+            Style::default()
+                .remove_modifier(Modifier::ITALIC)
+                .add_modifier(Modifier::BOLD)
+                .fg(Color::LightYellow),
+        );
+    text.extend(prayer_text);
+    Ok(Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .scroll(window.get_offset())
+        .alignment(Alignment::Center)
+        .style(Style::default().add_modifier(Modifier::ITALIC))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(
+                    Line::from(language.name() + &fallback_mark(&language, &source))
+                        .right_aligned(),
+                )
+                .border_type(BorderType::Rounded),
+        ))
+}
+
 pub fn render_progress<'a>(window: &mut Window) -> Paragraph<'a> {
     let mut progress = Paragraph::new(if window.has_error() {
         window.error()
     } else {
-        window.rosary.progress()
+        window.rosary.progress(window.get_language())
     })
     .alignment(Alignment::Right)
     .block(
         Block::default()
             .borders(Borders::ALL)
             .style(Style::default().fg(Color::White))
-            .title("Oratio")
+            .title(get_ui_translation("oratio", window.get_language()))
             .border_type(BorderType::Rounded),
     );
     if window.has_error() {
@@ -116,14 +220,17 @@ pub fn render_progress<'a>(window: &mut Window) -> Paragraph<'a> {
     progress
 }
 
-pub fn render_mysteries<'a>() -> Paragraph<'a> {
+pub fn render_mysteries<'a>(window: &Window) -> Paragraph<'a> {
     let progress = Paragraph::new(get_daily_mystery())
         .alignment(Alignment::Right)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .style(Style::default().fg(Color::White))
-                .title("Mysteria Rosarii")
+                .title(get_ui_translation(
+                    "mysteria_rosarii",
+                    window.get_language(),
+                ))
                 .border_type(BorderType::Rounded),
         );
     progress
@@ -134,7 +241,10 @@ pub fn render_volume<'a>(frame: &mut Frame) -> Gauge<'a> {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Volume")
+                .title(get_ui_translation(
+                    "volumen",
+                    frame.get_active_window_ro().get_language(),
+                ))
                 .border_type(BorderType::Rounded),
         )
         .gauge_style(Style::default().fg(Color::White).bg(Color::Black))
@@ -151,7 +261,10 @@ pub fn render_keybindings<'a>(frame: &mut Frame) -> Paragraph<'a> {
             Block::default()
                 .borders(Borders::ALL)
                 .style(Style::default().fg(Color::White))
-                .title("Keybindings")
+                .title(get_ui_translation(
+                    "claves",
+                    frame.get_active_window_ro().get_language(),
+                ))
                 .border_type(BorderType::Rounded),
         )
 }
@@ -166,7 +279,10 @@ pub fn render_error<'a>(frame: &mut Frame) -> Paragraph<'a> {
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Red))
                 .style(Style::default().fg(Color::Red))
-                .title("Error")
+                .title(get_ui_translation(
+                    "error",
+                    frame.get_active_window_ro().get_language(),
+                ))
                 .border_type(BorderType::Rounded),
         )
 }
@@ -178,23 +294,34 @@ pub fn render_calendar<'a>(
     window: &mut Window,
 ) -> Table<'a> {
     let mut items = vec![];
-    let mut i = 0;
-    let mut al = al.to_vec();
-    if al[0].1.year() == today.year() {
-        al.push(("Today", today.naive_local().date()));
+    let mut dates = al.to_vec();
+    if dates[0].1.year() == today.year() {
+        dates.push((TODAY, today.naive_local().date()));
     }
-    al.sort_by(|a, b| a.1.cmp(&b.1));
+    dates.sort_by_key(|a| a.1);
     let mut today_index = 0;
-    for (name, date) in al {
-        if i >= window.get_offset().0 {
-            items.push(Row::new(vec![date.to_string(), name.to_owned()]));
+    for (i, (name, date)) in dates.into_iter().enumerate() {
+        if i >= window.get_offset().0 as usize {
+            let shown = if name == TODAY {
+                get_ui_translation("hodie", window.get_language())
+            } else {
+                name.to_owned()
+            };
+            let mut row = Row::new(vec![date.to_string(), shown]);
+            if al.is_praeceptum(name) {
+                row = row.style(
+                    Style::default()
+                        .fg(Color::LightYellow)
+                        .add_modifier(Modifier::BOLD),
+                );
+            }
+            items.push(row);
         }
-        if name == "Today" {
+        if name == TODAY {
             today_index = i;
         }
-        i += 1;
     }
-    window.calendar_state.select(Some(today_index.into()));
+    window.calendar_state.select(Some(today_index));
     Table::new(
         items,
         [
@@ -210,20 +337,33 @@ pub fn render_calendar<'a>(
             .add_modifier(Modifier::REVERSED),
     )
     .highlight_symbol(">>")
-    .header(Row::new(vec!["Date".to_owned(), "Name".to_owned()]).bottom_margin(1))
+    .header(
+        Row::new(vec![
+            get_ui_translation("dies", window.get_language()),
+            get_ui_translation("nomen", window.get_language()),
+        ])
+        .bottom_margin(1),
+    )
     .block(
         Block::default()
-            .title("Calendar")
+            .title(get_ui_translation("calendarium", window.get_language()))
+            .title(
+                Line::from(get_ui_translation(
+                    &(String::from(REGIO_NAMESPACE) + &window.get_regio().name),
+                    window.get_language(),
+                ))
+                .right_aligned(),
+            )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded),
     )
 }
 
 pub fn render_month<'a>(
-    _al: &AnnusLiturgicus,
+    al: &AnnusLiturgicus,
     selected_day: DateTime<Local>,
     today: DateTime<Local>,
-    _window: &Window,
+    window: &Window,
 ) -> Result<Table<'a>, Box<dyn Error>> {
     let mut day = NaiveDate::from_ymd_opt(selected_day.year(), selected_day.month(), 1)
         .ok_or("Date could not be parsed")?;
@@ -235,6 +375,16 @@ pub fn render_month<'a>(
     }
     while day.month() == selected_day.month() {
         let mut d = Cell::from(format!("{:0>2}", day.day()));
+        let mut style = Style::default();
+        match al.ieiunium(day, window.disciplina, window.omnes_feriae_sextae) {
+            Some((Poenitentia::Abstinentia, _)) => style = style.add_modifier(Modifier::UNDERLINED),
+            Some(_) => style = style.fg(Color::Magenta).add_modifier(Modifier::UNDERLINED),
+            None => {}
+        }
+        if al.dies_praecepti(day) {
+            style = style.fg(Color::LightYellow).add_modifier(Modifier::BOLD);
+        }
+        d = d.style(style);
         if day == selected_day.naive_local().date() {
             d = d.style(
                 Style::default()
@@ -285,7 +435,60 @@ pub fn render_month<'a>(
     .style(Style::default().fg(Color::White))
     .row_highlight_style(Style::default().add_modifier(Modifier::ITALIC))
     .highlight_symbol(">>")
-    .header(Row::new(vec!["", "Su", "Mo", "Tu", "We", "Th", "Fr", "Sa", ""]).bottom_margin(1)))
+    .header(Row::new(weekday_header(window.get_language())).bottom_margin(1)))
+}
+
+/// Abbreviated names of the days of the week from Sunday, with empty margins
+fn weekday_header(lan: &Language) -> Vec<String> {
+    let mut header = vec![String::new()];
+    header.extend(WEEKDAYS.iter().map(|day| get_ui_translation(day, lan)));
+    header.push(String::new());
+    header
+}
+
+pub fn render_day<'a>(
+    al: &AnnusLiturgicus,
+    selected_day: DateTime<Local>,
+    window: &Window,
+) -> Paragraph<'a> {
+    let lan = window.get_language();
+    let date = selected_day.naive_local().date();
+    let mut lines = vec![Line::from(date.to_string())];
+    for (name, d) in al.to_vec() {
+        if d == date {
+            let mut line = Line::from(name);
+            if al.is_praeceptum(name) {
+                line = line.style(Style::default().fg(Color::LightYellow));
+            }
+            lines.push(line);
+        }
+    }
+    if al.dies_praecepti(date) {
+        lines.push(
+            Line::from(get_ui_translation("dies_praecepti", lan))
+                .style(Style::default().fg(Color::LightYellow)),
+        );
+    }
+    if let Some((poenitentia, causa)) =
+        al.ieiunium(date, window.disciplina, window.omnes_feriae_sextae)
+    {
+        lines.push(
+            Line::from(format!(
+                "{} ({})",
+                get_ui_translation(&format!("poenitentia/{}", poenitentia), lan),
+                get_ui_translation(&format!("causa/{}", causa), lan)
+            ))
+            .style(Style::default().fg(Color::Magenta)),
+        );
+    }
+    Paragraph::new(Text::from(lines))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(get_ui_translation(&window.disciplina.to_string(), lan))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
 }
 
 pub fn draw_rosary(
@@ -301,16 +504,23 @@ pub fn draw_rosary(
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(main_split[1]);
+    let prayer_chunks = bilingual_split(window, main_split[0]);
 
     let prayer_window = render_prayer(window);
     if prayer_window.is_err() {
         window.set_error(prayer_window.as_ref().err().as_ref().unwrap().to_string());
     }
-    rect.render_widget(prayer_window.unwrap(), main_split[0]);
+    rect.render_widget(prayer_window.unwrap(), prayer_chunks[0]);
+    if let Some(chunk) = prayer_chunks.get(1) {
+        match render_prayer_translation(window) {
+            Ok(translation) => rect.render_widget(translation, *chunk),
+            Err(err) => window.set_error(err.to_string()),
+        }
+    }
     rect.render_widget(render_progress(window), bottom_bar[0]);
-    rect.render_widget(render_mysteries(), bottom_bar[1]);
+    rect.render_widget(render_mysteries(window), bottom_bar[1]);
 
-    window.set_parent_dims(chunk.width, chunk.height);
+    window.set_parent_dims(prayer_chunks[0].width, chunk.height);
     Ok(())
 }
 
@@ -319,24 +529,44 @@ pub fn draw_prayer_set(
     rect: &mut ratatui::Frame,
     chunk: &mut Rect,
 ) -> Result<(), Box<dyn Error>> {
+    let prayer_chunks = bilingual_split(window, *chunk);
     let prayer_window = render_prayer_set(window);
     if prayer_window.is_err() {
         window.set_error(prayer_window.as_ref().err().as_ref().unwrap().to_string());
     }
-    rect.render_widget(prayer_window.unwrap(), *chunk);
+    rect.render_widget(prayer_window.unwrap(), prayer_chunks[0]);
+    if let Some(chunk) = prayer_chunks.get(1) {
+        match render_prayer_set_translation(window) {
+            Ok(translation) => rect.render_widget(translation, *chunk),
+            Err(err) => window.set_error(err.to_string()),
+        }
+    }
 
-    window.set_parent_dims(chunk.width, chunk.height);
+    window.set_parent_dims(prayer_chunks[0].width, chunk.height);
     Ok(())
 }
 
+/// Split `chunk` in two halves for the translation if shown side by side
+fn bilingual_split(window: &Window, chunk: Rect) -> Vec<Rect> {
+    if window.get_bilingual() == Bilingual::SideBySide {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunk)
+            .to_vec()
+    } else {
+        vec![chunk]
+    }
+}
+
 pub fn draw_frame_popup(frame: &mut Frame, rect: &mut ratatui::Frame, chunk: &mut Rect) {
     if frame.get_popup().is_none() {
         return;
     }
-    match frame.get_popup().unwrap() {
-        &Popup::Volume => draw_volume_popup(frame, rect, chunk),
-        &Popup::KeyBindings => draw_keybinding_popup(frame, rect, chunk),
-        &Popup::Error => draw_error_popup(frame, rect, chunk),
+    match *frame.get_popup().unwrap() {
+        Popup::Volume => draw_volume_popup(frame, rect, chunk),
+        Popup::KeyBindings => draw_keybinding_popup(frame, rect, chunk),
+        Popup::Error => draw_error_popup(frame, rect, chunk),
     }
 }
 
@@ -372,17 +602,82 @@ pub fn draw_calendar(
     let selected_day = today
         .checked_add_signed(Duration::days(day_offset.into()))
         .unwrap();
-    let al = AnnusLiturgicus::new(selected_day.year())?;
+    let al = AnnusLiturgicus::new(selected_day.year(), window.get_regio())?;
     rect.render_stateful_widget(
         render_calendar(&al, selected_day, today, window),
         split[0],
         &mut window.calendar_state,
     );
+    let month_split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(10), Constraint::Min(0)])
+        .split(split[1]);
     rect.render_stateful_widget(
         render_month(&al, selected_day, today, window)?,
-        split[1],
+        month_split[0],
         &mut window.month_state,
     );
+    rect.render_widget(render_day(&al, selected_day, window), month_split[1]);
+    Ok(())
+}
+
+/// Matrix of prayer files against languages, with missing texts in red and missing
+/// titles in yellow
+pub fn render_coverage<'a>(window: &mut Window) -> Result<Table<'a>, Box<dyn Error>> {
+    let lan = window.get_language().clone();
+    let offset = window.get_offset().0;
+    let coverage = window.get_coverage()?;
+    let rows: Vec<Row> = coverage
+        .files
+        .iter()
+        .skip(offset.into())
+        .map(|file| {
+            let cells = file.presence.iter().map(|presence| {
+                let color = if !presence.text {
+                    Color::Red
+                } else if presence.is_gap() {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                };
+                Cell::from(presence.abbreviation()).style(Style::default().fg(color))
+            });
+            Row::new(std::iter::once(Cell::from(file.file.clone())).chain(cells))
+        })
+        .collect();
+    let file_width = coverage
+        .files
+        .iter()
+        .map(|file| file.file.width())
+        .max()
+        .unwrap_or(0);
+    let widths = std::iter::once(Constraint::Length(file_width as u16)).chain(
+        coverage
+            .languages
+            .iter()
+            .map(|l| Constraint::Length(l.to_string().width().max(3) as u16)),
+    );
+    let header =
+        std::iter::once(String::new()).chain(coverage.languages.iter().map(|l| l.to_string()));
+    Ok(Table::new(rows, widths)
+        .style(Style::default().fg(Color::White))
+        .column_spacing(2)
+        .header(Row::new(header).bottom_margin(1))
+        .block(
+            Block::default()
+                .title(get_ui_translation("translationes", &lan))
+                .title(Line::from(get_ui_translation("legenda", &lan)).right_aligned())
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        ))
+}
+
+pub fn draw_coverage(
+    window: &mut Window,
+    rect: &mut ratatui::Frame,
+    chunk: &mut Rect,
+) -> Result<(), Box<dyn Error>> {
+    rect.render_widget(render_coverage(window)?, *chunk);
     Ok(())
 }
 
@@ -400,11 +695,8 @@ pub fn redraw(
 ) -> Result<(), Box<dyn Error>> {
     terminal.draw(|rect| {
         let mut chunk: Rect = rect.area();
-        let e = redraw_recursive(&mut frame.ws, rect, &mut chunk);
-        if e.is_err() {
-            frame
-                .get_active_window()
-                .set_error(e.unwrap_err().to_string());
+        if let Err(e) = redraw_recursive(&mut frame.ws, rect, &mut chunk) {
+            frame.get_active_window().set_error(e.to_string());
             draw_error_popup(frame, rect, &mut chunk);
         } else {
             draw_frame_popup(frame, rect, &mut chunk);
@@ -461,6 +753,7 @@ fn redraw_window(
     match window.active_menu_item() {
         MenuItem::Rosary => draw_rosary(window, rect, chunk),
         MenuItem::Calendar => draw_calendar(window, rect, chunk),
+        MenuItem::Coverage => draw_coverage(window, rect, chunk),
         MenuItem::Settings => Ok(()),
         MenuItem::Quit => Ok(()),
         MenuItem::_NOQUIT => Ok(()),
@@ -1,12 +1,16 @@
 use std::error::Error;
-use std::fs;
+use std::fmt;
 
 use crate::calender::get_daily_mystery_enum;
 use crate::prayer::Prayer;
 use ratatui::style::Color;
 
-use crate::config::{INITIUM_FILE, MYSTERY_DIR, PRAYER_DIR};
-use crate::language::{get_title_translation, ordinal_n_acc, ordinal_n_acc_upper, ordinal_n_gen};
+use crate::config::{INITIUM_FILE, MYSTERY_DIR};
+use crate::corpus::{self, Corpus};
+use crate::data::Layers;
+use crate::language::{
+    get_title_translation, get_ui_phrase, get_ui_phrase_in, ordinal_n_acc_upper, Language,
+};
 use crate::rosary::Mysteries::{Glorious, Joyful, Luminous, Sorrowful};
 use crate::rosary::RosaryPrayer::{
     ApostlesCreed, FatimaOMyJesus, FifthMystery, FinalPrayer, FirstMystery, FourthMystery, GloryBe,
@@ -16,7 +20,7 @@ use crate::rosary::RosaryPrayer::{
 };
 use crate::tui::Window;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum Mysteries {
     Joyful,
     Sorrowful,
@@ -50,6 +54,31 @@ pub enum RosaryPrayer {
 }
 
 impl RosaryPrayer {
+    /// Every prayer of the rosary
+    pub const ALL: [RosaryPrayer; 21] = [
+        RosaryPrayer::None,
+        SignOfCross,
+        ApostlesCreed,
+        OurFather,
+        HailMary,
+        HailMaryFaith,
+        HailMaryHope,
+        HailMaryCharity,
+        GloryBe,
+        FatimaOMyJesus,
+        HailHolyQueen,
+        PrayerToStMichael,
+        PrayerToStJoseph,
+        PrayerForPriests,
+        FirstMystery,
+        SecondMystery,
+        ThirdMystery,
+        FourthMystery,
+        FifthMystery,
+        Laudetur,
+        FinalPrayer,
+    ];
+
     /// Return corresponding file name
     fn get_file(&self) -> String {
         match self {
@@ -91,16 +120,42 @@ impl RosaryPrayer {
         }
     }
 
-    pub fn get_prayer_text(&self, window: &Window) -> Result<String, Box<dyn Error>> {
-        let file = PRAYER_DIR.to_owned() + "/" + &window.language() + "/" + &self.get_file();
-        let text = fs::read_to_string(&file)
-            .unwrap_or(format!("Unable find prayer {:?}\n at {}", self, &file));
+    /// Text of the prayer with the language it was taken from, which is a fallback of
+    /// `language` if the prayer is missing there
+    pub fn get_prayer_text(
+        &self,
+        window: &Window,
+        language: &Language,
+    ) -> Result<(String, Language), Box<dyn Error>> {
+        let (text, lan) = corpus::text_in(language, &self.get_file()).unwrap_or_else(|| {
+            let file = language.to_string() + "/" + &self.get_file();
+            (
+                format!("Unable find prayer {:?}\n at {}", self, file),
+                language.clone(),
+            )
+        });
+        Ok((self.add_mystery(window, text, &lan)?, lan))
+    }
+
+    /// Text of the prayer in `lan` only, e.g. as a translation
+    pub fn get_prayer_text_for(
+        &self,
+        window: &Window,
+        lan: &Language,
+    ) -> Result<String, Box<dyn Error>> {
+        self.add_mystery(window, self.get_prayer_text_for_language(lan), lan)
+    }
+
+    /// Insert the mystery into a Hail Mary, in the language of its text
+    fn add_mystery(
+        &self,
+        window: &Window,
+        text: String,
+        lan: &Language,
+    ) -> Result<String, Box<dyn Error>> {
         if self == &HailMary {
-            let mystery_addition = fs::read_to_string(
-                PRAYER_DIR.to_owned() + "/" + &window.language() + "/" + &get_mysteries_file(),
-            );
-            if mystery_addition.is_ok() {
-                let mystery_addition = mystery_addition.unwrap();
+            let mystery_addition = corpus::text(&(lan.to_string() + "/" + &get_mysteries_file()));
+            if let Ok(mystery_addition) = mystery_addition {
                 let mut mystery_additions = mystery_addition.split("\n");
                 (mystery_additions.advance_by((window.rosary.decade - 1) as usize))
                     .expect("Mystery addition file incomplete");
@@ -110,79 +165,51 @@ impl RosaryPrayer {
                 ));
             }
         } else if self == &HailMaryFaith {
-            return initial_hail_mary_addition(0, window, text);
+            return initial_hail_mary_addition(0, lan, text);
         } else if self == &HailMaryHope {
-            return initial_hail_mary_addition(1, window, text);
+            return initial_hail_mary_addition(1, lan, text);
         } else if self == &HailMaryCharity {
-            return initial_hail_mary_addition(2, window, text);
+            return initial_hail_mary_addition(2, lan, text);
         }
         Ok(text)
     }
 
-    pub fn get_prayer_title(&self, window: &mut Window) -> String {
-        let title = get_title_translation(&self.get_file(), window.get_language());
-        return match self {
-            FirstMystery => format!(
-                "{} Mysterium nuntiatur:\n{}",
-                ordinal_n_acc_upper(1),
-                title.trim()
-            ),
-            SecondMystery => format!(
-                "{} Mysterium nuntiatur:\n{}",
-                ordinal_n_acc_upper(2),
-                title.trim()
-            ),
-            ThirdMystery => format!(
-                "{} Mysterium nuntiatur:\n{}",
-                ordinal_n_acc_upper(3),
-                title.trim()
-            ),
-            FourthMystery => format!(
-                "{} Mysterium nuntiatur:\n{}",
-                ordinal_n_acc_upper(4),
-                title.trim()
-            ),
-            FifthMystery => format!(
-                "{} Mysterium nuntiatur:\n{}",
-                ordinal_n_acc_upper(5),
-                title.trim()
-            ),
-            HailMaryFaith => format!(
-                "{} {}",
-                title,
-                get_title_translation("pro_fide", window.get_language())
-            ),
-            HailMaryHope => format!(
-                "{} {}",
-                title,
-                get_title_translation("pro_spe", window.get_language())
-            ),
-            HailMaryCharity => format!(
-                "{} {}",
-                title,
-                get_title_translation("pro_caritate", window.get_language())
-            ),
+    pub fn get_prayer_title(&self, lan: &Language) -> String {
+        let title = get_title_translation(&self.get_file(), lan);
+        match self {
+            FirstMystery => format!("{}\n{}", mystery_announcement(1, lan), title.trim()),
+            SecondMystery => format!("{}\n{}", mystery_announcement(2, lan), title.trim()),
+            ThirdMystery => format!("{}\n{}", mystery_announcement(3, lan), title.trim()),
+            FourthMystery => format!("{}\n{}", mystery_announcement(4, lan), title.trim()),
+            FifthMystery => format!("{}\n{}", mystery_announcement(5, lan), title.trim()),
+            HailMaryFaith => format!("{} {}", title, get_title_translation("pro_fide", lan)),
+            HailMaryHope => format!("{} {}", title, get_title_translation("pro_spe", lan)),
+            HailMaryCharity => format!("{} {}", title, get_title_translation("pro_caritate", lan)),
             _ => title,
-        };
+        }
     }
 }
 
 impl Prayer for RosaryPrayer {
     fn get_file(&self) -> String {
-        return self.get_file();
+        self.get_file()
     }
 }
 
+/// "Primum Mysterium nuntiatur:" in `lan`, in Latin if no language has the phrase
+fn mystery_announcement(n: u8, lan: &Language) -> String {
+    get_ui_phrase("positio/mysterium", &[n], lan)
+        .unwrap_or_else(|| format!("{} Mysterium nuntiatur:", ordinal_n_acc_upper(n)))
+}
+
 fn initial_hail_mary_addition(
     n: usize,
-    window: &Window,
+    lan: &Language,
     text: String,
 ) -> Result<String, Box<dyn Error>> {
-    let mystery_addition = fs::read_to_string(
-        PRAYER_DIR.to_owned() + "/" + &window.language() + "/" + MYSTERY_DIR + "/" + INITIUM_FILE,
-    );
-    if mystery_addition.is_ok() {
-        let mystery_addition = mystery_addition.unwrap();
+    let mystery_addition =
+        corpus::text(&(lan.to_string() + "/" + MYSTERY_DIR + "/" + INITIUM_FILE));
+    if let Ok(mystery_addition) = mystery_addition {
         let mut mystery_additions = mystery_addition.split("\n");
         mystery_additions
             .advance_by(n)
@@ -192,11 +219,11 @@ fn initial_hail_mary_addition(
             &format!("Jesus,\n{}.", mystery_additions.next().unwrap_or("")),
         ));
     }
-    return Ok(text);
+    Ok(text)
 }
 
-impl ToString for Mysteries {
-    fn to_string(&self) -> String {
+impl fmt::Display for Mysteries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mystery_adj = match self {
             Joyful => "Gaudiosa",
             Sorrowful => "Dolorosa",
@@ -204,7 +231,7 @@ impl ToString for Mysteries {
             Luminous => "Luminosa",
         };
 
-        format!("Mysteria {}", mystery_adj)
+        write!(f, "Mysteria {}", mystery_adj)
     }
 }
 
@@ -250,6 +277,12 @@ pub struct Rosary {
     num_prayer: u8,
 }
 
+impl Default for Rosary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Rosary {
     pub fn new() -> Rosary {
         Rosary {
@@ -261,7 +294,7 @@ impl Rosary {
     }
 
     pub fn to_prayer(&self) -> RosaryPrayer {
-        self.prayers_for_bead()[(self.prayer - 1) as usize].clone()
+        self.prayers_for_bead()[(self.prayer - 1) as usize]
     }
 
     fn prayers_for_bead(&self) -> Vec<RosaryPrayer> {
@@ -289,21 +322,17 @@ impl Rosary {
                     },
                     OurFather,
                 ],
-                i if i >= 1 && i <= 10 => vec![HailMary],
+                i if (1..=10).contains(&i) => vec![HailMary],
                 11 => vec![GloryBe, FatimaOMyJesus],
-                12 => {
-                    if self.decade == 5 {
-                        vec![
-                            HailHolyQueen,
-                            PrayerToStJoseph,
-                            PrayerToStMichael,
-                            FinalPrayer,
-                            Laudetur,
-                            SignOfCross,
-                        ]
-                    } else {
-                        vec![]
-                    }
+                12 if self.decade == 5 => {
+                    vec![
+                        HailHolyQueen,
+                        PrayerToStJoseph,
+                        PrayerToStMichael,
+                        FinalPrayer,
+                        Laudetur,
+                        SignOfCross,
+                    ]
                 }
                 _ => {
                     vec![]
@@ -402,32 +431,32 @@ impl Rosary {
         }
     }
 
-    pub fn progress(&self) -> String {
-        let location;
-        if self.decade == 0 && self.bead == 0 {
-            location = String::from("ad crucifixum");
-        } else if self.decade == 0 && self.bead == 1 {
-            location = format!("ad {} nodum", ordinal_n_acc(self.bead));
-        } else if self.decade == 0 && self.bead == 5 {
-            location = String::from("post tergeminum granum");
-        } else if self.decade == 0 && self.bead == 6 {
-            location = format!("ad {} nodum", ordinal_n_acc(self.bead - 1));
-        } else if self.decade == 0 && self.bead > 1 && self.bead <= 4 {
-            location = format!("ad {} granum tergemini grani", ordinal_n_acc(self.bead - 1));
-        } else if self.bead == 0 {
-            location = format!("ante {} decennium", ordinal_n_acc(self.decade));
-        } else if self.bead == 11 {
-            location = format!("post {} decennium", ordinal_n_acc(self.decade));
-        } else if self.bead == 12 {
-            location = String::from("ad finem rosarii");
-        } else {
-            location = format!(
-                "ad {} nodum {} decennii",
-                ordinal_n_acc(self.bead),
-                ordinal_n_gen(self.decade)
-            )
+    /// Position of the hand on the rosary in `lan`, or else in the first of its fallback
+    /// languages having the phrases, or else the key of the phrase
+    pub fn progress(&self, lan: &Language) -> String {
+        corpus::with(|corpus, layers| self.progress_in(corpus, layers, lan))
+    }
+
+    /// `progress` on the titles of `layers`
+    pub fn progress_in(&self, corpus: &mut Corpus, layers: &Layers, lan: &Language) -> String {
+        let (key, numbers) = self.position();
+        let key = String::from("positio/") + key;
+        get_ui_phrase_in(corpus, layers, &key, &numbers, lan).unwrap_or(key)
+    }
+
+    /// Interface key of the phrase for the position of the hand and the numbers in it
+    fn position(&self) -> (&'static str, Vec<u8>) {
+        match (self.decade, self.bead) {
+            (0, 0) => ("crucifixum", vec![]),
+            (0, 1) => ("nodus", vec![1]),
+            (0, 2..=4) => ("granum", vec![self.bead - 1]),
+            (0, 5) => ("tergeminum", vec![]),
+            (0, 6) => ("nodus", vec![5]),
+            (_, 0) => ("ante_decennium", vec![self.decade]),
+            (_, 11) => ("post_decennium", vec![self.decade]),
+            (_, 12) => ("finis", vec![]),
+            _ => ("decennium", vec![self.bead, self.decade]),
         }
-        format!("Manus {}.", location)
     }
 
     pub fn get_decade(&self) -> u8 {
@@ -0,0 +1,316 @@
+//! Transcription of Church Slavonic texts for readers and terminals without the old
+//! orthography
+
+/// ISO 15924 code of Church Slavonic Cyrillic, the script of the languages transcribed
+pub const CYRILLIC_SLAVONIC: &str = "Cyrs";
+
+/// How Church Slavonic texts are written
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Scriptura {
+    /// Church Slavonic Cyrillic with titla and accents, as in the prayer files
+    Ecclesiastica,
+    /// Church Slavonic Cyrillic with abbreviations written out and without combining
+    /// marks, for terminals lacking the fonts
+    SineTitlis,
+    /// Modern civil Cyrillic
+    Civilis,
+    Glagolitica,
+    /// Latin transliteration
+    Latina,
+}
+
+impl Scriptura {
+    pub fn cycle(self) -> Scriptura {
+        match self {
+            Scriptura::Ecclesiastica => Scriptura::SineTitlis,
+            Scriptura::SineTitlis => Scriptura::Civilis,
+            Scriptura::Civilis => Scriptura::Glagolitica,
+            Scriptura::Glagolitica => Scriptura::Latina,
+            Scriptura::Latina => Scriptura::Ecclesiastica,
+        }
+    }
+}
+
+/// Abbreviations under titlo, without accents, and the stems they stand for
+const ABBREVIATIONS: [(&str, &str); 16] = [
+    ("гдⷭ҇", "господ"),
+    ("іи҃с", "іисус"),
+    ("хрⷭ҇т", "христ"),
+    ("сн҃", "сын"),
+    ("бж҇", "бож"),
+    ("бг҃", "бог"),
+    ("бцⷣ", "богородиц"),
+    ("дх҃", "дух"),
+    ("ст҃", "свят"),
+    ("мл҃тв", "молитв"),
+    ("млⷭ҇т", "милост"),
+    ("бл҃г", "благ"),
+    ("чл҃в", "челов"),
+    ("нб҃", "неб"),
+    ("цр҃", "цар"),
+    ("мт҃р", "матер"),
+];
+
+/// Letters of the old orthography and their civil spelling
+const CIVIL_LETTERS: [(char, &str); 20] = [
+    ('ѣ', "е"),
+    ('і', "и"),
+    ('ї', "и"),
+    ('ѵ', "и"),
+    ('ѧ', "я"),
+    ('ꙗ', "я"),
+    ('ѩ', "я"),
+    ('ѫ', "у"),
+    ('ѭ', "ю"),
+    ('ѹ', "у"),
+    ('ꙋ', "у"),
+    ('ѡ', "о"),
+    ('ѻ', "о"),
+    ('ѿ', "от"),
+    ('ѳ', "ф"),
+    ('ѯ', "кс"),
+    ('ѱ', "пс"),
+    ('ѕ', "з"),
+    ('ꙁ', "з"),
+    ('є', "е"),
+];
+
+const LATIN_LETTERS: [(char, &str); 33] = [
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('д', "d"),
+    ('е', "e"),
+    ('ё', "e"),
+    ('ж', "zh"),
+    ('з', "z"),
+    ('и', "i"),
+    ('й', "j"),
+    ('к', "k"),
+    ('л', "l"),
+    ('м', "m"),
+    ('н', "n"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "kh"),
+    ('ц', "ts"),
+    ('ч', "ch"),
+    ('ш', "sh"),
+    ('щ', "shch"),
+    ('ъ', ""),
+    ('ы', "y"),
+    ('ь', ""),
+    ('э', "e"),
+    ('ю', "yu"),
+    ('я', "ya"),
+];
+
+/// Capital Glagolitic letters, the small ones follow at an offset of 0x30
+const GLAGOLITIC_LETTERS: [(char, &str); 41] = [
+    ('а', "\u{2C00}"),
+    ('б', "\u{2C01}"),
+    ('в', "\u{2C02}"),
+    ('г', "\u{2C03}"),
+    ('д', "\u{2C04}"),
+    ('е', "\u{2C05}"),
+    ('є', "\u{2C05}"),
+    ('ж', "\u{2C06}"),
+    ('ѕ', "\u{2C07}"),
+    ('з', "\u{2C08}"),
+    ('ꙁ', "\u{2C08}"),
+    ('и', "\u{2C09}"),
+    ('й', "\u{2C09}"),
+    ('і', "\u{2C0B}"),
+    ('ї', "\u{2C0B}"),
+    ('к', "\u{2C0D}"),
+    ('л', "\u{2C0E}"),
+    ('м', "\u{2C0F}"),
+    ('н', "\u{2C10}"),
+    ('о', "\u{2C11}"),
+    ('ѻ', "\u{2C11}"),
+    ('п', "\u{2C12}"),
+    ('р', "\u{2C13}"),
+    ('с', "\u{2C14}"),
+    ('т', "\u{2C15}"),
+    ('у', "\u{2C16}"),
+    ('ѹ', "\u{2C16}"),
+    ('ꙋ', "\u{2C16}"),
+    ('ф', "\u{2C17}"),
+    ('ѳ', "\u{2C2A}"),
+    ('х', "\u{2C18}"),
+    ('ѡ', "\u{2C19}"),
+    ('ѿ', "\u{2C19}\u{2C15}"),
+    ('щ', "\u{2C1B}"),
+    ('ц', "\u{2C1C}"),
+    ('ч', "\u{2C1D}"),
+    ('ш', "\u{2C1E}"),
+    ('ъ', "\u{2C1F}"),
+    ('ы', "\u{2C1F}\u{2C0B}"),
+    ('ь', "\u{2C20}"),
+    ('ѣ', "\u{2C21}"),
+];
+
+/// Glagolitic letters for the yuses and other letters not in `GLAGOLITIC_LETTERS`
+const GLAGOLITIC_YUSES: [(char, &str); 9] = [
+    ('ю', "\u{2C23}"),
+    ('ѧ', "\u{2C24}"),
+    ('я', "\u{2C24}"),
+    ('ꙗ', "\u{2C21}"),
+    ('ѩ', "\u{2C27}"),
+    ('ѫ', "\u{2C28}"),
+    ('ѭ', "\u{2C29}"),
+    ('ѵ', "\u{2C2B}"),
+    ('ѯ', "\u{2C0D}\u{2C14}"),
+];
+
+/// Write the Church Slavonic `text` in `scriptura`
+pub fn transcribe(text: &str, scriptura: Scriptura) -> String {
+    match scriptura {
+        Scriptura::Ecclesiastica => text.to_string(),
+        Scriptura::SineTitlis => sine_titlis(text),
+        Scriptura::Civilis => civilis(text),
+        Scriptura::Glagolitica => map_words(&sine_titlis(text), glagolitica),
+        Scriptura::Latina => map_words(&civilis(text), |word| {
+            map_letters(word, |c| lookup(&LATIN_LETTERS, c))
+        }),
+    }
+}
+
+/// Accents, breathings and titla
+fn is_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}' | '\u{0483}'..='\u{0489}' | '\u{A66F}'..='\u{A67D}')
+}
+
+/// Letters written above others in abbreviations
+fn superscript(c: char) -> Option<&'static str> {
+    const LETTERS: [&str; 32] = [
+        "б", "в", "г", "д", "ж", "з", "к", "л", "м", "н", "о", "п", "р", "с", "т", "х", "ц", "ч",
+        "ш", "щ", "ѳ", "ст", "а", "е", "ꙉ", "ꙋ", "ѣ", "ю", "ꙗ", "ѧ", "ѫ", "ѭ",
+    ];
+    let i = (c as u32).checked_sub(0x2DE0)?;
+    LETTERS.get(i as usize).copied()
+}
+
+fn lookup(table: &[(char, &'static str)], c: char) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(letter, _)| *letter == c)
+        .map(|(_, s)| *s)
+}
+
+/// Apply `f` to each word of `text`, leaving everything in between as it is
+fn map_words<F: Fn(&str) -> String>(text: &str, f: F) -> String {
+    let mut mapped = String::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_alphabetic() || is_mark(c) || superscript(c).is_some() {
+            word.push(c);
+        } else {
+            mapped += &f(&word);
+            word.clear();
+            mapped.push(c);
+        }
+    }
+    mapped + &f(&word)
+}
+
+/// Replace the letters `f` knows in `word`, keeping the case of capitals
+fn map_letters<F: Fn(char) -> Option<&'static str>>(word: &str, f: F) -> String {
+    let mut mapped = String::new();
+    for c in word.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match f(lower) {
+            Some(s) if c != lower => {
+                let mut chars = s.chars();
+                mapped.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+                mapped.extend(chars);
+            }
+            Some(s) => mapped += s,
+            None => mapped.push(c),
+        }
+    }
+    mapped
+}
+
+/// Write out the abbreviations and drop all combining marks
+fn sine_titlis(text: &str) -> String {
+    map_words(text, |word| {
+        let plain: String = word
+            .chars()
+            .filter(|c| !matches!(c, '\u{0300}'..='\u{036F}' | '\u{0486}' | '\u{0485}'))
+            .map(|c| match c {
+                'ѐ' => 'е',
+                'ѝ' => 'и',
+                'Ѐ' => 'Е',
+                'Ѝ' => 'И',
+                c => c,
+            })
+            .collect();
+        let lower = plain.to_lowercase();
+        let expanded = ABBREVIATIONS
+            .iter()
+            .find(|(abbreviation, _)| lower.starts_with(abbreviation))
+            .map(|(abbreviation, stem)| {
+                let stem = if plain.starts_with(char::is_uppercase) {
+                    capitalize(stem)
+                } else {
+                    stem.to_string()
+                };
+                stem + &lower[abbreviation.len()..]
+            })
+            .unwrap_or(plain);
+        expanded
+            .chars()
+            .filter(|c| !is_mark(*c))
+            .map(|c| superscript(c).map_or_else(|| c.to_string(), String::from))
+            .collect()
+    })
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Civil Cyrillic as in the orthography since 1918
+fn civilis(text: &str) -> String {
+    map_words(&sine_titlis(text), |word| {
+        let word = word.replace("оу", "у").replace("Оу", "У");
+        let mut word = map_letters(&word, |c| lookup(&CIVIL_LETTERS, c));
+        if word.ends_with(['ъ', 'Ъ']) && word.chars().count() > 1 {
+            word.pop();
+        }
+        word
+    })
+}
+
+fn glagolitica(word: &str) -> String {
+    let word = word.replace("оу", "у").replace("Оу", "У");
+    let mut mapped = String::new();
+    for c in word.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match lookup(&GLAGOLITIC_LETTERS, lower).or_else(|| lookup(&GLAGOLITIC_YUSES, lower)) {
+            Some(s) => {
+                for (i, g) in s.chars().enumerate() {
+                    // only the first letter of a capital stays capital
+                    if c != lower && i == 0 {
+                        mapped.push(g);
+                    } else {
+                        mapped.extend(char::from_u32(g as u32 + 0x30));
+                    }
+                }
+            }
+            None => mapped.push(c),
+        }
+    }
+    mapped
+}
@@ -1,15 +1,25 @@
 use crate::audio::{audio_thread, AudioCommand};
-use crate::config_parse::get_all_prayset_titles;
+use crate::calender::{save_regio, saved_regio, AnnusLiturgicus, Disciplina, Regio};
+use crate::config_parse::{get_all_prayset_titles, PrayerSetConfig};
+use crate::corpus::{self, Corpus};
+use crate::coverage::Coverage;
+use crate::data::{self, Layers};
+use crate::directio::{is_rtl, visual};
 use crate::events::{
     calendar_input_handler, general_input_handler, prayer_set_input_handler, volume_input_handler,
 };
+use crate::orthographia::{scribe, Orthographia, LATIN};
 use crate::prayer::PrayerSet;
 use crate::rosary::Rosary;
-use crate::{events::rosary_input_handler, language::Language};
+use crate::scriptura::{transcribe, Scriptura, CYRILLIC_SLAVONIC};
+use crate::{
+    events::rosary_input_handler,
+    language::{fallback_chain, Language},
+};
 use chrono::Datelike;
 use crossterm::event::KeyEvent;
 use rand::rngs::StdRng;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 
 use ratatui::widgets::TableState;
 use ratatui::{backend::CrosstermBackend, Terminal};
@@ -18,9 +28,11 @@ use std::fmt;
 use std::io::Stdout;
 use std::sync::mpsc::{self, Receiver, Sender};
 
-use crate::language::Language::LATINA;
 use crate::render::redraw;
 
+/// Seeds drawn on reshuffle are kept short enough to be shared by word of mouth
+const SEED_RANGE: u64 = 1_000_000;
+
 #[derive(Debug)]
 pub enum ErrorString {
     Error(&'static str),
@@ -48,6 +60,7 @@ pub enum MenuItem {
     Rosary,
     PrayerSet(usize),
     Calendar,
+    Coverage,
     Settings,
     Quit,
 }
@@ -56,16 +69,94 @@ pub enum MenuItem {
 pub enum Event<I> {
     Input(I),
     Refresh(u16, u16),
+    /// Files in the prayer directory changed
+    Reload,
     Tick,
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum WindowStack {
     HSplit(Box<WindowStack>, Box<WindowStack>),
     VSplit(Box<WindowStack>, Box<WindowStack>),
     Node(Window),
 }
 
+impl WindowStack {
+    /// Return all windows, from left to right and top to bottom
+    pub fn windows_mut(&mut self) -> Vec<&mut Window> {
+        match self {
+            WindowStack::Node(w) => vec![w],
+            WindowStack::HSplit(v, w) | WindowStack::VSplit(v, w) => {
+                let mut windows = v.windows_mut();
+                windows.append(&mut w.windows_mut());
+                windows
+            }
+        }
+    }
+
+    /// Move the focus to the next window, from left to right and top to bottom
+    pub fn focus_next(&mut self) {
+        let mut windows = self.windows_mut();
+        let active = windows.iter().position(|w| w.is_active).unwrap_or(0);
+        let next = (active + 1) % windows.len();
+        windows[active].is_active = false;
+        windows[next].is_active = true;
+    }
+
+    /// Link or unlink the active window. A newly linked window takes the rosary
+    /// position of the other linked windows
+    pub fn toggle_link(&mut self) {
+        let rosary = self
+            .windows_mut()
+            .into_iter()
+            .find(|w| w.linked && !w.is_active)
+            .map(|w| w.rosary);
+        let Some(window) = _get_active_window(self) else {
+            return;
+        };
+        window.linked = !window.linked;
+        if let Some(rosary) = rosary.filter(|_| window.linked) {
+            window.rosary = rosary;
+        }
+    }
+
+    /// Move all linked windows to the rosary position of the active window, if linked
+    pub fn sync_linked(&mut self) {
+        let Some(active) = _get_active_window_read_only(self) else {
+            return;
+        };
+        if !active.linked {
+            return;
+        }
+        let rosary = active.rosary;
+        for window in self.windows_mut() {
+            if window.linked {
+                window.rosary = rosary;
+            }
+        }
+    }
+}
+
+/// How a window shows its prayer in a second language as well
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Bilingual {
+    Off,
+    SideBySide,
+    /// Each line followed by the line of the translation
+    Interlinear,
+}
+
+impl Bilingual {
+    pub fn cycle(self) -> Self {
+        match self {
+            Bilingual::Off => Bilingual::SideBySide,
+            Bilingual::SideBySide => Bilingual::Interlinear,
+            Bilingual::Interlinear => Bilingual::Off,
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum Popup {
     Volume,
@@ -79,10 +170,12 @@ pub struct Frame {
     tx: Sender<AudioCommand>,
     popup: Option<Popup>,
     volume: f32,
+    /// Seed given on the command line, used for every new window
+    seed: Option<u64>,
 }
 
-fn new_ws_box() -> Result<Box<WindowStack>, E> {
-    Ok(Box::from(WindowStack::Node(Window::new()?)))
+fn new_ws_box(seed: Option<u64>) -> Result<Box<WindowStack>, E> {
+    Ok(Box::from(WindowStack::Node(Window::new(seed)?)))
 }
 
 pub fn _get_active_window(s: &mut WindowStack) -> Option<&mut Window> {
@@ -111,14 +204,14 @@ pub fn _get_active_window(s: &mut WindowStack) -> Option<&mut Window> {
             }
         }
     };
-    return None;
+    None
 }
 
 pub fn _get_active_window_read_only(s: &WindowStack) -> Option<&Window> {
     match s {
         WindowStack::Node(w) => {
             if w.is_active {
-                return Some(&w);
+                return Some(w);
             }
         }
         WindowStack::HSplit(v, w) => {
@@ -140,12 +233,12 @@ pub fn _get_active_window_read_only(s: &WindowStack) -> Option<&Window> {
             }
         }
     };
-    return None;
+    None
 }
 
 impl Frame {
-    pub fn new() -> Result<Frame, E> {
-        let mut w = Window::new()?;
+    pub fn new(seed: Option<u64>) -> Result<Frame, E> {
+        let mut w = Window::new(seed)?;
         w.is_active = true;
         let ws = WindowStack::Node(w);
         let (tx, rx) = mpsc::channel();
@@ -155,6 +248,7 @@ impl Frame {
             tx,
             popup: None,
             volume: 1.0,
+            seed,
         })
     }
 
@@ -205,29 +299,27 @@ impl Frame {
     }
 
     pub fn get_active_window(&mut self) -> &mut Window {
-        return _get_active_window(&mut self.ws).unwrap();
+        _get_active_window(&mut self.ws).unwrap()
     }
 
     pub fn get_active_window_ro(&self) -> &Window {
-        return _get_active_window_read_only(&self.ws).unwrap();
+        _get_active_window_read_only(&self.ws).unwrap()
     }
 
     pub fn vsplit(mut self) -> (Frame, Result<(), E>) {
-        let ws_box = new_ws_box();
-        if ws_box.is_err() {
-            return (self, Err(ws_box.unwrap_err()));
-        }
-        let ws_box = ws_box.unwrap();
+        let ws_box = match new_ws_box(self.seed) {
+            Ok(ws_box) => ws_box,
+            Err(err) => return (self, Err(err)),
+        };
         self.ws = WindowStack::VSplit(Box::from(self.ws), ws_box);
         (self, Ok(()))
     }
 
     pub fn hsplit(mut self) -> (Frame, Result<(), E>) {
-        let ws_box = new_ws_box();
-        if ws_box.is_err() {
-            return (self, Err(ws_box.unwrap_err()));
-        }
-        let ws_box = ws_box.unwrap();
+        let ws_box = match new_ws_box(self.seed) {
+            Ok(ws_box) => ws_box,
+            Err(err) => return (self, Err(err)),
+        };
         self.ws = WindowStack::HSplit(Box::from(self.ws), ws_box);
         (self, Ok(()))
     }
@@ -237,17 +329,42 @@ impl Frame {
         self.get_active_window().last_error = error;
     }
 
+    pub fn focus_next(&mut self) {
+        self.ws.focus_next()
+    }
+
+    pub fn toggle_link(&mut self) {
+        self.ws.toggle_link()
+    }
+
+    pub fn sync_linked(&mut self) {
+        self.ws.sync_linked()
+    }
+
+    /// Rebuild the prayer sets of all windows after the prayer directory changed
+    pub fn reload(&mut self) {
+        corpus::invalidate();
+        let mut error = None;
+        for window in self.ws.windows_mut() {
+            if let Err(err) = window.reload() {
+                error = Some(err.to_string());
+            }
+        }
+        if let Some(error) = error {
+            self.set_error(error);
+        }
+    }
+
     pub fn check_error(&mut self) {
         let le = self.get_active_window().last_error.clone();
-        if le != "" {
+        if !le.is_empty() {
             self.set_error(le);
         }
     }
 
     pub fn toggle_audio(&mut self) -> R {
         let caw = self.get_active_window();
-        if caw.audio.is_some() {
-            let audio = caw.audio.as_ref().unwrap().to_owned();
+        if let Some(audio) = caw.audio.clone() {
             caw.is_playing = true;
             self.tx.send(AudioCommand::Play(audio))?;
         } else {
@@ -263,6 +380,15 @@ pub struct Window {
     x: i16,
     y: i16,
     lang: Language,
+    /// Language of the translation in bilingual mode
+    translation: Language,
+    bilingual: Bilingual,
+    /// How Church Slavonic texts are written
+    scriptura: Scriptura,
+    /// How vowels of Latin texts are marked
+    orthographia: Orthographia,
+    /// Whether to highlight the stressed syllables of Latin texts
+    accentus: bool,
     parent_h: u16,
     parent_w: u16,
     pub last_error: String,
@@ -270,42 +396,94 @@ pub struct Window {
     is_playing: bool,
     pub audio: Option<String>,
     pub is_active: bool,
+    /// Follows the rosary position of the other linked windows
+    linked: bool,
     pub rosary: Rosary,
     pub prayersets: Vec<PrayerSet>,
-    _rng: StdRng,
+    /// Seed prayer sets are selected with until reshuffled
+    seed: u64,
+    /// Draws the seeds for reshuffling prayer sets
+    rng: StdRng,
+    /// Translation coverage, built when the tab is first shown and again after reloading
+    coverage: Option<Coverage>,
     pub calendar_state: TableState,
     pub month_state: TableState,
+    pub disciplina: Disciplina,
+    /// Whether to keep Friday abstinence outside of Lent under the current discipline
+    pub omnes_feriae_sextae: bool,
+    regiones: Vec<Regio>,
+    /// Index into `regiones`
+    regio: usize,
 }
 
 impl Window {
-    pub fn new() -> Result<Window, E> {
-        let today = chrono::offset::Local::now().date_naive().num_days_from_ce() as u64;
-        let mut rng = StdRng::seed_from_u64(today);
+    /// Create a window, the prayer sets are selected by `seed` or else by the day
+    pub fn new(seed: Option<u64>) -> Result<Window, E> {
+        let regiones = corpus::regiones()?;
+        let regio = saved_regio(&regiones);
+        Window::new_in(
+            data::layers(),
+            &get_all_prayset_titles()?,
+            regiones,
+            regio,
+            seed,
+        )
+    }
+
+    /// Create a window on the languages of `layers` with the prayer sets of `configs`,
+    /// in the region at index `regio` of `regiones`
+    pub fn new_in(
+        layers: &Layers,
+        configs: &[PrayerSetConfig],
+        regiones: Vec<Regio>,
+        regio: usize,
+        seed: Option<u64>,
+    ) -> Result<Window, E> {
+        let date = chrono::offset::Local::now().date_naive();
+        let seed = seed.unwrap_or(date.num_days_from_ce() as u64);
+        let al = AnnusLiturgicus::new(date.year(), &regiones[regio])?;
 
         let mut prayersets = vec![];
-        for (title, yaml) in get_all_prayset_titles()? {
-            prayersets.push(PrayerSet::new(title, yaml, &mut rng)?)
+        for config in configs {
+            prayersets.push(PrayerSet::new(config, seed, &al, date)?)
         }
+        let languages = Corpus::default().languages(layers).to_vec();
+        let lang = Language::initial_in(&languages);
+        let translation = fallback_chain(&lang.to_string(), &languages)
+            .first()
+            .map_or_else(|| lang.clone(), |dir| Language::new(dir));
         Ok(Window {
             x: 0,
             y: 0,
-            lang: LATINA,
+            translation,
+            bilingual: Bilingual::Off,
+            scriptura: Scriptura::Ecclesiastica,
+            orthographia: Orthographia::Longae,
+            accentus: false,
+            lang,
             parent_h: 0,
             parent_w: 0,
             last_error: String::from(""),
             item: MenuItem::Rosary,
             is_active: false,
+            linked: false,
             is_playing: false,
             audio: None,
             rosary: Rosary::new(),
             prayersets,
-            _rng: rng,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            coverage: None,
             calendar_state: TableState::default().with_selected(0),
             month_state: TableState::default().with_selected(0),
+            disciplina: Disciplina::Hodierna,
+            omnes_feriae_sextae: false,
+            regiones,
+            regio,
         })
     }
     pub fn active_menu_item(&self) -> MenuItem {
-        return self.item;
+        self.item
     }
 
     pub fn get_offset(&self) -> (u16, u16) {
@@ -323,7 +501,7 @@ impl Window {
         if content_height >= self.parent_h as usize {
             0
         } else {
-            ((self.parent_h as usize - content_height) / 2) as usize
+            (self.parent_h as usize - content_height) / 2
         }
     }
 
@@ -332,7 +510,7 @@ impl Window {
         if content_width >= self.parent_w as usize {
             0
         } else {
-            ((self.parent_w as usize - content_width) / 2) as usize
+            (self.parent_w as usize - content_width) / 2
         }
     }
 
@@ -347,9 +525,7 @@ impl Window {
     }
 
     pub fn up(&mut self) {
-        if self.x != i16::MAX {
-            self.x += 1;
-        }
+        self.x = self.x.saturating_add(1);
     }
 
     pub fn left(&mut self) {
@@ -359,9 +535,7 @@ impl Window {
     }
 
     pub fn right(&mut self) {
-        if self.y != i16::MAX {
-            self.y += 1;
-        }
+        self.y = self.y.saturating_add(1);
     }
 
     pub fn reset_horizontal_scroll(&mut self) {
@@ -386,7 +560,7 @@ impl Window {
     }
 
     pub fn has_error(&self) -> bool {
-        self.last_error != String::from("")
+        !self.last_error.is_empty()
     }
 
     pub fn error(&self) -> String {
@@ -398,22 +572,89 @@ impl Window {
     }
 
     pub fn cycle_language(&mut self) {
-        match self.lang {
-            Language::GERMANA => {
-                self.lang = Language::LATINA;
-            }
-            Language::LATINA => {
-                self.lang = Language::ANGLIA;
-            }
-            Language::ANGLIA => {
-                self.lang = Language::SLAVONICA;
-            }
-            Language::SLAVONICA => {
-                self.lang = Language::GERMANA;
-            }
+        self.lang = self.lang.next();
+    }
+
+    pub fn get_translation(&self) -> &Language {
+        &self.translation
+    }
+
+    /// Cycle the language of the translation, skipping the language of the window
+    pub fn cycle_translation(&mut self) {
+        let next = self.translation.next();
+        self.translation = if next == self.lang { next.next() } else { next };
+    }
+
+    pub fn get_scriptura(&self) -> Scriptura {
+        self.scriptura
+    }
+
+    pub fn cycle_scriptura(&mut self) {
+        self.scriptura = self.scriptura.cycle();
+    }
+
+    pub fn get_orthographia(&self) -> Orthographia {
+        self.orthographia
+    }
+
+    pub fn cycle_orthographia(&mut self) {
+        self.orthographia = self.orthographia.cycle();
+    }
+
+    pub fn toggle_accentus(&mut self) {
+        self.accentus = !self.accentus;
+    }
+
+    /// `text` in `lan` as it is displayed in this window
+    pub fn transcribe(&self, lan: &Language, text: String) -> String {
+        let info = lan.info();
+        if info.script == CYRILLIC_SLAVONIC {
+            transcribe(&text, self.scriptura)
+        } else if info.code == LATIN {
+            scribe(&text, self.orthographia, self.accentus)
+        } else if is_rtl(&info.script) {
+            visual(&text)
+        } else {
+            text
         }
     }
 
+    pub fn is_linked(&self) -> bool {
+        self.linked
+    }
+
+    pub fn get_bilingual(&self) -> Bilingual {
+        self.bilingual
+    }
+
+    pub fn cycle_bilingual(&mut self) {
+        self.bilingual = self.bilingual.cycle()
+    }
+
+    pub fn cycle_disciplina(&mut self) {
+        self.disciplina = self.disciplina.cycle()
+    }
+
+    pub fn toggle_omnes_feriae_sextae(&mut self) {
+        self.omnes_feriae_sextae = !self.omnes_feriae_sextae
+    }
+
+    pub fn get_regio(&self) -> &Regio {
+        &self.regiones[self.regio]
+    }
+
+    /// Switch to the next region and keep it for the next start. The prayer sets are
+    /// rebuilt, since their conditions on feasts depend on the transfers of the region
+    pub fn cycle_regio(&mut self) -> R {
+        let regio = (self.regio + 1) % self.regiones.len();
+        let (prayersets, item) = self.rebuilt_prayersets(&self.regiones[regio])?;
+        save_regio(&self.regiones[regio])?;
+        self.regio = regio;
+        self.prayersets = prayersets;
+        self.item = item;
+        Ok(())
+    }
+
     pub fn set_language(&mut self, l: &Language) {
         self.lang = (*l).clone()
     }
@@ -435,7 +676,8 @@ impl Window {
                     MenuItem::Calendar
                 }
             }
-            MenuItem::Calendar => MenuItem::Rosary,
+            MenuItem::Calendar => MenuItem::Coverage,
+            MenuItem::Coverage => MenuItem::Rosary,
             _ => self.item,
         }
     }
@@ -447,6 +689,68 @@ impl Window {
         }
     }
 
+    /// Select the prayers of the current prayer set anew with a new seed
+    pub fn reshuffle(&mut self) -> R {
+        let date = chrono::offset::Local::now().date_naive();
+        let al = AnnusLiturgicus::new(date.year(), self.get_regio())?;
+        let seed = self.rng.gen_range(0, SEED_RANGE);
+        self.get_curr_prayer_set()?.reshuffle(seed, &al, date)
+    }
+
+    /// Rebuild the prayer sets from the configuration. Prayer sets keep their seed and
+    /// position, as long as their title stays the same. The translation coverage is
+    /// built anew when next shown. Nothing else changes on error
+    pub fn reload(&mut self) -> R {
+        self.coverage = None;
+        // the region stays chosen by name, the first one if it is gone
+        let regiones = corpus::regiones()?;
+        let regio = regiones
+            .iter()
+            .position(|r| r.name == self.get_regio().name)
+            .unwrap_or(0);
+        let (prayersets, item) = self.rebuilt_prayersets(&regiones[regio])?;
+        self.regiones = regiones;
+        self.regio = regio;
+        self.prayersets = prayersets;
+        self.item = item;
+        Ok(())
+    }
+
+    /// The prayer sets of the configuration in `regio`, keeping seed and position of
+    /// those with the same title, and the menu item showing the same prayer set
+    fn rebuilt_prayersets(&self, regio: &Regio) -> Result<(Vec<PrayerSet>, MenuItem), E> {
+        let date = chrono::offset::Local::now().date_naive();
+        let al = AnnusLiturgicus::new(date.year(), regio)?;
+        let mut prayersets = vec![];
+        for config in get_all_prayset_titles()? {
+            prayersets.push(
+                match self.prayersets.iter().find(|p| p.get_key() == config.title) {
+                    Some(prayer_set) => prayer_set.rebuild(&config, &al, date)?,
+                    None => PrayerSet::new(&config, self.seed, &al, date)?,
+                },
+            );
+        }
+        let item = match self.item {
+            MenuItem::PrayerSet(i) => {
+                let title = self.prayersets.get(i).map(|p| p.get_key());
+                prayersets
+                    .iter()
+                    .position(|p| Some(p.get_key()) == title)
+                    .map_or(MenuItem::Rosary, MenuItem::PrayerSet)
+            }
+            item => item,
+        };
+        Ok((prayersets, item))
+    }
+
+    /// The translation coverage, built on first use
+    pub fn get_coverage(&mut self) -> Result<&Coverage, E> {
+        if self.coverage.is_none() {
+            self.coverage = Some(Coverage::new()?);
+        }
+        Ok(self.coverage.as_ref().unwrap())
+    }
+
     pub fn get_curr_prayer_set(&mut self) -> Result<&mut PrayerSet, E> {
         let i = self.get_curr_prayer_set_index().unwrap_or(0);
         self.prayersets
@@ -474,48 +778,42 @@ pub fn popup_input_handler(
         return Ok(None);
     }
     let popup = popup.unwrap();
-    match popup {
-        &Popup::Volume => return volume_input_handler(terminal, frame, event),
-        &Popup::KeyBindings => return Ok(None),
-        &Popup::Error => return Ok(None),
+    match *popup {
+        Popup::Volume => volume_input_handler(terminal, frame, event),
+        Popup::KeyBindings => Ok(None),
+        Popup::Error => Ok(None),
     }
 }
 
 impl Error for InvalidFocusError {}
 
-pub fn input_handler<'a>(
+pub fn input_handler(
     rx: &Receiver<Event<KeyEvent>>,
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     mut frame: Frame,
 ) -> (Frame, Result<MenuItem, Box<dyn Error>>) {
     // Input handler
-    let evnt = rx.recv();
-    if evnt.is_err() {
+    let Ok(evnt) = rx.recv() else {
         return (frame, Err(Box::new(InvalidFocusError)));
-    }
-    match rx.recv().unwrap() {
+    };
+    match evnt {
         Event::Refresh(_, _) => {
-            let a = redraw(terminal, &mut frame);
-            if a.is_err() {
-                return (frame, Err(a.unwrap_err()));
+            if let Err(err) = redraw(terminal, &mut frame) {
+                return (frame, Err(err));
             }
             (frame, Ok(MenuItem::_NOQUIT))
         }
         Event::Input(event) => {
             let (mut frame, gih) = general_input_handler(terminal, frame, &event);
-            if gih.is_err() {
-                return (frame, Err(gih.unwrap_err()));
-            }
-            let gih = gih.unwrap();
+            let gih = match gih {
+                Ok(gih) => gih,
+                Err(err) => return (frame, Err(err)),
+            };
             if gih == MenuItem::_NOQUIT {
-                let popup_new_menu_item = popup_input_handler(terminal, &mut frame, &event);
-                if popup_new_menu_item.is_err() {
-                    return (frame, Err(popup_new_menu_item.unwrap_err()));
-                } else {
-                    let popup_new_menu_item = popup_new_menu_item.unwrap();
-                    if popup_new_menu_item.is_some() {
-                        return (frame, Ok(popup_new_menu_item.unwrap()));
-                    }
+                match popup_input_handler(terminal, &mut frame, &event) {
+                    Err(err) => return (frame, Err(err)),
+                    Ok(Some(menu_item)) => return (frame, Ok(menu_item)),
+                    Ok(None) => {}
                 }
                 let ami = frame.get_active_window().active_menu_item();
                 match ami {
@@ -538,6 +836,14 @@ pub fn input_handler<'a>(
                 (frame, Ok(gih))
             }
         }
+        Event::Reload => {
+            frame.reload();
+            if let Err(err) = redraw(terminal, &mut frame) {
+                return (frame, Err(err));
+            }
+            let active_menu_item = frame.get_active_window().active_menu_item();
+            (frame, Ok(active_menu_item))
+        }
         Event::Tick => (frame, Ok(MenuItem::_NOQUIT)),
     }
 }
@@ -547,11 +853,13 @@ pub fn key_listen<'a>(
     terminal: &'a mut Terminal<CrosstermBackend<Stdout>>,
     frame: Frame,
 ) -> (Frame, MenuItem) {
-    let (mut frame, new_menu_item) = input_handler(&rx, terminal, frame);
-    if new_menu_item.is_err() {
-        frame.set_error(new_menu_item.unwrap_err().to_string());
-        let ami = frame.get_active_window().active_menu_item();
-        return (frame, ami);
+    let (mut frame, new_menu_item) = input_handler(rx, terminal, frame);
+    match new_menu_item {
+        Ok(menu_item) => (frame, menu_item),
+        Err(err) => {
+            frame.set_error(err.to_string());
+            let ami = frame.get_active_window().active_menu_item();
+            (frame, ami)
+        }
     }
-    (frame, new_menu_item.unwrap())
 }
@@ -1,11 +1,19 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::Text,
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use crate::{language::get_title_translation, tui::Window};
+use crate::{
+    language::get_title_translation,
+    markup::{linea, Continuatio, Pars, OPTIO_END, OPTIO_START, RUBRICA_END, RUBRICA_START},
+    orthographia::{STRESS_END, STRESS_START},
+    tui::Window,
+};
 
 pub fn paragraph<'a>(text: String, title: &str, w: &mut Window) -> Paragraph<'a> {
     Paragraph::new(Text::from(text))
@@ -20,12 +28,12 @@ pub fn paragraph<'a>(text: String, title: &str, w: &mut Window) -> Paragraph<'a>
         )
 }
 
-pub fn cursive<'a>(p: Paragraph) -> Paragraph {
+pub fn cursive(p: Paragraph) -> Paragraph {
     p
 }
 
 pub fn title_from_s<'a>(t: String, w: &Window) -> Text<'a> {
-    let mut t = Text::from(hcenter(&String::from("\n".to_owned() + &t), w));
+    let mut t = styled(&hcenter(&("\n".to_owned() + &t), w));
     t = t.patch_style(
         Style::default()
             .remove_modifier(Modifier::ITALIC)
@@ -42,7 +50,7 @@ pub fn cursive_p<'a>(
     w: &mut Window,
 ) -> Paragraph<'a> {
     let title = title_from_s(title, w);
-    let mut text = Text::from(hcenter(&text, w));
+    let mut text = styled(&hcenter(&text, w));
     text = text.patch_style(
         Style::default()
             .add_modifier(Modifier::ITALIC)
@@ -52,13 +60,164 @@ pub fn cursive_p<'a>(
     combine_to_p(text, title, border_title, w)
 }
 
-pub fn hcenter(text: &String, window: &Window) -> String {
-    let mut text_width = 0;
-    for line in text.lines() {
-        if text_width < line.len() {
-            text_width = line.len();
+/// Text with its markup styled, see `markup`, and the syllables between `STRESS_START`
+/// and `STRESS_END` highlighted
+pub fn styled<'a>(text: &str) -> Text<'a> {
+    if text.is_empty() {
+        return Text::raw("");
+    }
+    let mut continuatio = Continuatio::default();
+    Text::from(
+        text.lines()
+            .map(|line| styled_in(line, &mut continuatio))
+            .collect::<Vec<Line>>(),
+    )
+}
+
+/// Style `line`, which may continue the versicle, response or leader line before
+fn styled_in<'a>(line: &str, continuatio: &mut Continuatio) -> Line<'a> {
+    let linea = linea(line, continuatio);
+    let rubrica = Style::default()
+        .fg(Color::Red)
+        .remove_modifier(Modifier::ITALIC);
+    // whether in a rubric, in an optional part and in a stressed syllable
+    let style = |(in_rubrica, in_optio, stress): (bool, bool, bool)| {
+        let style = if in_rubrica {
+            rubrica
+        } else if in_optio {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+        if stress {
+            style
+                .add_modifier(Modifier::UNDERLINED)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            style
+        }
+    };
+    let mut spans = vec![
+        Span::raw(linea.indent.to_string()),
+        Span::styled(linea.signum.to_string(), rubrica),
+    ];
+    let mut state = (false, false, false);
+    let mut span = String::new();
+    let mut rest = linea.text;
+    while let Some(c) = rest.chars().next() {
+        let mut next = state;
+        let len = if rest.starts_with(OPTIO_START) {
+            next.1 = true;
+            OPTIO_START.len()
+        } else if rest.starts_with(OPTIO_END) {
+            next.1 = false;
+            OPTIO_END.len()
+        } else {
+            match c {
+                RUBRICA_START => next.0 = true,
+                RUBRICA_END => next.0 = false,
+                STRESS_START => next.2 = true,
+                STRESS_END => next.2 = false,
+                c => span.push(c),
+            }
+            c.len_utf8()
+        };
+        rest = &rest[len..];
+        if next != state {
+            spans.push(Span::styled(std::mem::take(&mut span), style(state)));
+            state = next;
         }
     }
+    spans.push(Span::styled(span, style(state)));
+    let style = match linea.pars {
+        Some(Pars::Versiculus | Pars::Praecentor) => Style::default().add_modifier(Modifier::BOLD),
+        Some(Pars::Responsorium) | None => Style::default(),
+    };
+    Line::from(
+        spans
+            .into_iter()
+            .filter(|span| !span.content.is_empty())
+            .collect::<Vec<Span>>(),
+    )
+    .style(style)
+}
+
+/// Follow each line of `text` by the line of `translation` at the same position, a pair
+/// of empty lines becomes a single one. Also returns which lines are translations
+pub fn interleave(text: &str, translation: &str) -> (String, Vec<bool>) {
+    let text: Vec<&str> = text.trim_end().lines().collect();
+    let translation: Vec<&str> = translation.trim_end().lines().collect();
+    let mut lines = vec![];
+    let mut translated = vec![];
+    for i in 0..text.len().max(translation.len()) {
+        let line = text.get(i).copied().unwrap_or("");
+        let translation = translation.get(i).copied().unwrap_or("");
+        if line.trim().is_empty() && translation.trim().is_empty() {
+            lines.push("");
+            translated.push(false);
+            continue;
+        }
+        lines.extend([line, translation]);
+        translated.extend([false, true]);
+    }
+    (lines.join("\n"), translated)
+}
+
+/// Style interleaved lines, the translations dimmed
+pub fn interlinear<'a>(lines: String, translated: &[bool]) -> Text<'a> {
+    let mut continuationes = (Continuatio::default(), Continuatio::default());
+    let lines: Vec<Line> = lines
+        .split('\n')
+        .zip(translated)
+        .map(|(line, translated)| {
+            let continuatio = if *translated {
+                &mut continuationes.1
+            } else {
+                &mut continuationes.0
+            };
+            let style = if *translated {
+                Style::default()
+                    .fg(Color::Gray)
+                    .remove_modifier(Modifier::ITALIC)
+            } else {
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::ITALIC)
+            };
+            styled_in(line, continuatio).patch_style(style)
+        })
+        .collect();
+    Text::from(lines)
+}
+
+/// Like `cursive_p`, with the lines of `translation` under those of `text`
+pub fn interlinear_p<'a>(
+    text: String,
+    translation: String,
+    border_title: String,
+    title: String,
+    w: &mut Window,
+) -> Paragraph<'a> {
+    let title = title_from_s(title, w);
+    let (lines, translated) = interleave(&text, &translation);
+    let text = interlinear(hcenter(&lines, w), &translated);
+    combine_to_p(text, title, border_title, w)
+}
+
+/// Columns `line` takes in the terminal once styled, which lays it out by grapheme
+/// clusters like ratatui does
+pub fn line_width(line: &str) -> usize {
+    let linea = linea(line, &mut Continuatio::default());
+    let text = linea.text.replace(OPTIO_START, "").replace(OPTIO_END, "");
+    (linea.indent.to_string() + linea.signum + &text)
+        .graphemes(true)
+        .filter(|g| !g.starts_with([STRESS_START, STRESS_END, RUBRICA_START, RUBRICA_END]))
+        .map(|g| g.width())
+        .sum()
+}
+
+pub fn hcenter(text: &str, window: &Window) -> String {
+    let text_width = text.lines().map(line_width).max().unwrap_or(0);
     let v_offset = window.get_vert_offset(text_width);
     let offset_string = " ".repeat(v_offset);
     offset_string.clone() + &text.replace("\n", &("\n".to_owned() + &offset_string))
@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::config_parse::user_config_file;
+use crate::data;
+use crate::tui::{Event, E};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Send `Event::Reload` whenever files in the data directories or the user configuration
+/// change
+pub fn watch_preces<I: Send + 'static>(tx: Sender<Event<I>>) -> Result<(), E> {
+    let mut last = latest_change();
+    thread::Builder::new()
+        .name("rosarium - watcher".to_string())
+        .spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let change = latest_change();
+            if change != last {
+                last = change;
+                if tx.send(Event::Reload).is_err() {
+                    break;
+                }
+            }
+        })?;
+    Ok(())
+}
+
+/// Latest modification time and number of files, which also notices removed files
+fn latest_change() -> (Option<SystemTime>, usize) {
+    let mut change = (None, 0);
+    for root in data::layers().roots() {
+        visit(&root, &mut change);
+    }
+    if let Some(user_config) = user_config_file() {
+        visit(&user_config, &mut change);
+    }
+    change
+}
+
+fn visit(path: &Path, change: &mut (Option<SystemTime>, usize)) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            visit(&entry.path(), change);
+        }
+    } else {
+        change.0 = change.0.max(metadata.modified().ok());
+        change.1 += 1;
+    }
+}